@@ -1,7 +1,7 @@
-use std::f32::{INFINITY, NEG_INFINITY};
+use crate::scalar::{INFINITY, NEG_INFINITY};
 use crate::{get_curves, get_points, CurveShape, FacetShape, Reshape, Model, Shape};
 use serde::{Deserialize, Serialize};
-use glam::*;
+use crate::scalar::*;
 
 #[derive(Clone, Default, Serialize, Deserialize)]
 #[serde(default = "Area::default")]
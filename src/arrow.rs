@@ -1,5 +1,5 @@
-use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, FRAC_PI_8};
-use glam::*;
+use crate::scalar::consts::{FRAC_PI_2, FRAC_PI_4, FRAC_PI_8};
+use crate::scalar::*;
 use crate::{log, CurveShape};
 
 const TWO_ARROWS: &str = "There should be two arrows or more.";
@@ -58,7 +58,7 @@ impl ToCurve for Vec<Arrow> {
 
 pub struct ArrowsToCurve {
     curve: CurveShape,
-    knot:  f32,
+    knot:  Float,
     arrow: Arrow,
 }
 
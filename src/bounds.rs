@@ -0,0 +1,170 @@
+use crate::scalar::*;
+use serde::{Deserialize, Serialize};
+
+const JACOBI_SWEEPS: usize = 16;
+
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Box3 {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Box3 {
+    pub fn from_points(points: &[Vec3]) -> Self {
+        if points.is_empty() {
+            return Box3::default();
+        }
+        Box3 {
+            min: points.iter().fold(Vec3::INFINITY, |a, b| a.min(*b)),
+            max: points.iter().fold(Vec3::NEG_INFINITY, |a, b| a.max(*b)),
+        }
+    }
+
+    pub fn union(&self, other: &Box3) -> Self {
+        Box3 {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+
+    pub fn intersects(&self, other: &Box3) -> bool {
+        self.min.cmple(other.max).all() && other.min.cmple(self.max).all()
+    }
+
+    pub fn get_center(&self) -> Vec3 {
+        (self.min + self.max) / 2.
+    }
+
+    pub fn get_size(&self) -> Vec3 {
+        self.max - self.min
+    }
+}
+
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub struct OrientedBox3 {
+    pub center: Vec3,
+    pub axes: Mat3,
+    pub half_size: Vec3,
+}
+
+impl OrientedBox3 {
+    // Box aligned to the principal axes of the points
+    pub fn from_points(points: &[Vec3]) -> Self {
+        if points.is_empty() {
+            return OrientedBox3 {
+                axes: Mat3::IDENTITY,
+                ..Default::default()
+            };
+        }
+        let mean = points.iter().sum::<Vec3>() / points.len() as Float;
+        let mut covariance = Mat3::ZERO;
+        for point in points {
+            let d = *point - mean;
+            covariance += Mat3::from_cols(d * d.x, d * d.y, d * d.z);
+        }
+        let mut axes = get_eigenvectors(covariance);
+        axes.z_axis = axes.x_axis.cross(axes.y_axis);
+        let local: Vec<Vec3> = points.iter().map(|p| axes.transpose() * *p).collect();
+        let bounds = Box3::from_points(&local);
+        OrientedBox3 {
+            center: axes * bounds.get_center(),
+            axes,
+            half_size: bounds.get_size() / 2.,
+        }
+    }
+
+    pub fn get_corners(&self) -> Vec<Vec3> {
+        let mut corners = vec![];
+        for i in 0..8 {
+            let sign = vec3(
+                if i & 1 == 0 {-1.} else {1.},
+                if i & 2 == 0 {-1.} else {1.},
+                if i & 4 == 0 {-1.} else {1.},
+            );
+            corners.push(self.center + self.axes * (self.half_size * sign));
+        }
+        corners
+    }
+}
+
+// Orthonormal eigenvectors of a symmetric matrix by cyclic Jacobi rotations
+fn get_eigenvectors(matrix: Mat3) -> Mat3 {
+    let mut a = matrix.to_cols_array_2d();
+    let mut v = Mat3::IDENTITY.to_cols_array_2d();
+    for _ in 0..JACOBI_SWEEPS {
+        for (p, q) in [(0, 1), (0, 2), (1, 2)] {
+            if a[p][q].abs() <= Float::EPSILON * (a[p][p].abs() + a[q][q].abs()) {
+                continue;
+            }
+            let theta = (a[q][q] - a[p][p]) / (2. * a[p][q]);
+            let t = theta.signum() / (theta.abs() + (theta * theta + 1.).sqrt());
+            let c = 1. / (t * t + 1.).sqrt();
+            let s = t * c;
+            for column in a.iter_mut().chain(v.iter_mut()) {
+                let (xp, xq) = (column[p], column[q]);
+                column[p] = c * xp - s * xq;
+                column[q] = s * xp + c * xq;
+            }
+            let (ap, aq) = (a[p], a[q]);
+            a[p] = [0, 1, 2].map(|k| c * ap[k] - s * aq[k]);
+            a[q] = [0, 1, 2].map(|k| s * ap[k] + c * aq[k]);
+        }
+    }
+    Mat3::from_cols_array_2d(&v).transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scalar::*;
+    use crate::CurveShape;
+    use super::{Box3, OrientedBox3};
+
+    // Corners of a 4 by 1 rectangle turned by 30 degrees about Z around (1, 2, 3)
+    fn get_rectangle() -> Vec<Vec3> {
+        let rotation = Mat3::from_rotation_z(consts::FRAC_PI_6);
+        [vec3(-2., -0.5, 0.), vec3(2., -0.5, 0.), vec3(2., 0.5, 0.), vec3(-2., 0.5, 0.)]
+            .map(|corner| rotation * corner + vec3(1., 2., 3.)).to_vec()
+    }
+
+    #[test]
+    fn oriented_box_follows_rotated_rectangle() {
+        let bounds = OrientedBox3::from_points(&get_rectangle());
+        assert!(bounds.center.distance(vec3(1., 2., 3.)) < 1e-5);
+        let axes = [bounds.axes.x_axis, bounds.axes.y_axis, bounds.axes.z_axis];
+        let long = vec3(consts::FRAC_PI_6.cos(), consts::FRAC_PI_6.sin(), 0.);
+        let (i, _) = bounds.half_size.to_array().iter().enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1)).unwrap();
+        assert!(axes[i].cross(long).length() < 1e-5);
+        let mut half_size = bounds.half_size.to_array();
+        half_size.sort_by(Float::total_cmp);
+        assert!(Vec3::from(half_size).distance(vec3(0., 0.5, 2.)) < 1e-5);
+        for corner in get_rectangle() {
+            assert!(bounds.get_corners().iter().any(|c| c.distance(corner) < 1e-5));
+        }
+    }
+
+    #[test]
+    fn axis_aligned_box_encloses_rotated_rectangle() {
+        let bounds = Box3::from_points(&get_rectangle());
+        let (sin, cos) = consts::FRAC_PI_6.sin_cos();
+        let half_size = vec3(2. * cos + 0.5 * sin, 2. * sin + 0.5 * cos, 0.);
+        assert!(bounds.min.distance(vec3(1., 2., 3.) - half_size) < 1e-5);
+        assert!(bounds.max.distance(vec3(1., 2., 3.) + half_size) < 1e-5);
+    }
+
+    #[test]
+    fn curve_boxes_honour_the_window() {
+        let mut line = CurveShape {
+            controls: vec![vec3(0., 0., 0.), vec3(4., 4., 0.)],
+            ..Default::default()
+        }.get_valid();
+        line.min = 0.25;
+        line.max = 0.5;
+        let bounds = line.get_box3();
+        assert!(bounds.min.distance(vec3(1., 1., 0.)) < 1e-5);
+        assert!(bounds.max.distance(vec3(2., 2., 0.)) < 1e-5);
+        let oriented = line.get_oriented_box3();
+        assert!(oriented.center.distance(vec3(1.5, 1.5, 0.)) < 1e-5);
+        assert!((oriented.half_size.max_element() - (0.5 as Float).sqrt()).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,215 @@
+use crate::scalar::consts::PI;
+use crate::{nurbs::Nurbs, CurveShape, Model, Reshape, Revolve, Shape};
+use serde::{Deserialize, Serialize};
+use crate::scalar::*;
+
+// Longest hyperbola parameter span of one rational quadratic segment
+const MAX_HYPERBOLIC_SPAN: Float = 1.;
+
+// Full ellipse, or the part from angle_a to angle_b in the parametric angle before rotation
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Ellipse {
+    pub center:   Vec2,
+    pub radii:    Vec2,
+    pub rotation: Float,
+    pub angle_a:  Float,
+    pub angle_b:  Float,
+    pub reverse:  bool,
+}
+
+impl Default for Ellipse {
+    fn default() -> Self {
+        Self {
+            center:   Vec2::ZERO,
+            radii:    Vec2::ONE,
+            rotation: 0.,
+            angle_a:  0.,
+            angle_b:  0.,
+            reverse:  false,
+        }
+    }
+}
+
+impl Ellipse {
+    pub fn get_shapes(&self) -> Vec<Shape> {
+        let mut angle = self.angle_b - self.angle_a;
+        if angle == 0. {
+            angle = PI * 2.;
+        }
+        // A unit circle arc scaled to the radii stays exact
+        let revolve = Revolve {
+            parts: vec![Model::Point(Vec2::from_angle(self.angle_a).extend(0.))],
+            axis:  vec3(0., 0., angle.signum()),
+            angle: angle.abs(),
+            ..Default::default()
+        };
+        get_conic_reshape(self.center, self.rotation, self.radii, self.reverse).get_reshapes(revolve.get_shapes())
+    }
+}
+
+// Parabola y = x^2 / (4 * focal_length) from x_a to x_b with its vertex at the given point
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Parabola {
+    pub vertex:       Vec2,
+    pub focal_length: Float,
+    pub x_a:          Float,
+    pub x_b:          Float,
+    pub rotation:     Float,
+    pub reverse:      bool,
+}
+
+impl Default for Parabola {
+    fn default() -> Self {
+        Self {
+            vertex:       Vec2::ZERO,
+            focal_length: 0.25,
+            x_a:          -1.,
+            x_b:          1.,
+            rotation:     0.,
+            reverse:      false,
+        }
+    }
+}
+
+impl Parabola {
+    pub fn get_shapes(&self) -> Vec<Shape> {
+        if self.focal_length == 0. || self.x_a == self.x_b {
+            return vec![];
+        }
+        let height = |x: Float| x * x / (4. * self.focal_length);
+        // The middle control is where the end tangents meet
+        let controls = vec![
+            vec3(self.x_a, height(self.x_a), 0.),
+            vec3((self.x_a + self.x_b) / 2., self.x_a * self.x_b / (4. * self.focal_length), 0.),
+            vec3(self.x_b, height(self.x_b), 0.),
+        ];
+        let shapes = get_conic_shapes(controls, vec![1.]);
+        get_conic_reshape(self.vertex, self.rotation, Vec2::ONE, self.reverse).get_reshapes(shapes)
+    }
+}
+
+// Right branch of x^2 / a^2 - y^2 / b^2 = 1 for radii (a, b) from y_a to y_b
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Hyperbola {
+    pub center:   Vec2,
+    pub radii:    Vec2,
+    pub y_a:      Float,
+    pub y_b:      Float,
+    pub rotation: Float,
+    pub reverse:  bool,
+}
+
+impl Default for Hyperbola {
+    fn default() -> Self {
+        Self {
+            center:   Vec2::ZERO,
+            radii:    Vec2::ONE,
+            y_a:      -1.,
+            y_b:      1.,
+            rotation: 0.,
+            reverse:  false,
+        }
+    }
+}
+
+impl Hyperbola {
+    pub fn get_shapes(&self) -> Vec<Shape> {
+        if self.radii.x == 0. || self.radii.y == 0. || self.y_a == self.y_b {
+            return vec![];
+        }
+        // Segments of (cosh t, sinh t) with middle weights cosh(span / 2) like cos(angle / 2) for circles
+        let t_a = (self.y_a / self.radii.y).asinh();
+        let t_b = (self.y_b / self.radii.y).asinh();
+        let count = ((t_b - t_a).abs() / MAX_HYPERBOLIC_SPAN).ceil().max(1.) as usize;
+        let span = (t_b - t_a) / count as Float;
+        let weight = (span / 2.).cosh();
+        let mut controls = vec![vec3(t_a.cosh(), t_a.sinh(), 0.)];
+        for i in 0..count {
+            let t = t_a + span * i as Float;
+            let middle = t + span / 2.;
+            controls.push(vec3(middle.cosh(), middle.sinh(), 0.) / weight);
+            controls.push(vec3((t + span).cosh(), (t + span).sinh(), 0.));
+        }
+        let shapes = get_conic_shapes(controls, vec![weight; count]);
+        get_conic_reshape(self.center, self.rotation, self.radii, self.reverse).get_reshapes(shapes)
+    }
+}
+
+// Rational quadratic segments joined at double knots, with points at both ends
+fn get_conic_shapes(controls: Vec<Vec3>, middle_weights: Vec<Float>) -> Vec<Shape> {
+    let count = middle_weights.len();
+    let mut knots = vec![0.; 3];
+    let mut weights = vec![1.];
+    for (i, weight) in middle_weights.iter().enumerate() {
+        let knot = (i + 1) as Float / count as Float;
+        knots.extend(if i + 1 < count {vec![knot; 2]} else {vec![knot; 3]});
+        weights.extend([*weight, 1.]);
+    }
+    let start = controls[0];
+    let end = controls[controls.len() - 1];
+    let curve = CurveShape::from_nurbs_and_controls(Nurbs {
+        order: 3,
+        knots,
+        weights,
+        ..Default::default()
+    }, controls);
+    vec![Shape::Point(start), Shape::Curve(curve), Shape::Point(end)]
+}
+
+fn get_conic_reshape(center: Vec2, rotation: Float, radii: Vec2, reverse: bool) -> Reshape {
+    Reshape {
+        position: center.extend(0.),
+        rotation: vec3(0., 0., rotation),
+        scale:    radii.extend(1.),
+        reverse,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_local_points(shapes: Vec<Shape>, center: Vec2, rotation: Float) -> Vec<Vec2> {
+        let curve = shapes.into_iter().find_map(|shape| match shape {
+            Shape::Curve(curve) => Some(curve),
+            _ => None,
+        }).expect("conic curve");
+        (0..=64).map(|i| {
+            let point = curve.get_point(i as Float / 64.).truncate();
+            Vec2::from_angle(-rotation).rotate(point - center)
+        }).collect()
+    }
+
+    #[test]
+    fn ellipse_points_are_on_the_ellipse() {
+        let ellipse = Ellipse {center: vec2(1., -1.), radii: vec2(3., 1.5), rotation: 0.4, ..Default::default()};
+        for point in get_local_points(ellipse.get_shapes(), ellipse.center, ellipse.rotation) {
+            assert!(((point / ellipse.radii).length_squared() - 1.).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn parabola_points_are_on_the_parabola() {
+        let parabola = Parabola {vertex: vec2(-1., 2.), focal_length: 0.5, x_a: -2., x_b: 3., rotation: -0.3, ..Default::default()};
+        let points = get_local_points(parabola.get_shapes(), parabola.vertex, parabola.rotation);
+        for point in &points {
+            assert!((point.y - point.x * point.x / 2.).abs() < 1e-4);
+        }
+        assert!((points[0].x + 2.).abs() < 1e-4 && (points[64].x - 3.).abs() < 1e-4);
+    }
+
+    #[test]
+    fn hyperbola_points_are_on_the_hyperbola() {
+        let hyperbola = Hyperbola {center: vec2(0.5, 0.5), radii: vec2(2., 1.), y_a: -3., y_b: 4., rotation: 0.2, ..Default::default()};
+        let points = get_local_points(hyperbola.get_shapes(), hyperbola.center, hyperbola.rotation);
+        for point in &points {
+            let scaled = *point / hyperbola.radii;
+            assert!((scaled.x * scaled.x - scaled.y * scaled.y - 1.).abs() < 1e-3);
+        }
+        assert!((points[0].y + 3.).abs() < 1e-4 && (points[64].y - 4.).abs() < 1e-4);
+    }
+}
@@ -3,7 +3,7 @@ use crate::{get_reshaped_point, get_shapes,
     CurveShape, FacetShape, Reshape, Model, Rectangle, Shape
 };
 use serde::{Deserialize, Serialize};
-use glam::*;
+use crate::scalar::*;
 
 #[derive(Clone, Serialize, Deserialize)]
 #[serde(default)] //  = "Extrude::default"
@@ -11,7 +11,7 @@ pub struct Extrude {
     pub parts:   Vec<Model>,
     pub reshape: Reshape,
     pub axis:    Vec3,//[f32; 3],
-    pub length:  f32,
+    pub length:  Float,
 }
 
 impl Default for Extrude {
@@ -81,7 +81,7 @@ impl Extrude {
         }
         self.reshape.get_reshapes(shapes) 
     }
-    pub fn from_area(area: Area, length: f32, reshape: &Reshape) -> Self {
+    pub fn from_area(area: Area, length: Float, reshape: &Reshape) -> Self {
         let mut model = Self::default();
         model.parts = vec![Model::Area(area)];
         model.length = length;
@@ -113,7 +113,7 @@ impl ExtrudeBasis {
 #[derive(Clone, Default, Serialize, Deserialize)]
 #[serde(default = "Cuboid::default")]
 pub struct Cuboid {
-    pub lengths: [f32; 3],
+    pub lengths: [Float; 3],
     pub reshape: Reshape,
 }
 
@@ -129,8 +129,8 @@ impl Cuboid {
 #[derive(Clone, Default, Serialize, Deserialize)]
 #[serde(default = "Cylinder::default")]
 pub struct Cylinder {
-    pub radius: f32,
-    pub length: f32,
+    pub radius: Float,
+    pub length: Float,
     pub center: Vec2,//[f32; 2],
     pub reshape: Reshape,
 }
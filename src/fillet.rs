@@ -0,0 +1,54 @@
+use crate::{get_curves, CurveShape, Model, Reshape, Shape};
+use serde::{Deserialize, Serialize};
+use crate::scalar::*;
+
+// Every corner between consecutive planar curves rounded with an arc of the radius
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Fillet {
+    pub parts:   Vec<Model>,
+    pub reshape: Reshape,
+    pub radius:  Float,
+}
+
+impl Fillet {
+    pub fn get_shapes(&self) -> Vec<Shape> {
+        let curves = get_cornered_curves(&self.parts, |a, b| CurveShape::fillet(a, b, self.radius));
+        self.reshape.get_reshapes(curves.into_iter().map(Shape::Curve).collect())
+    }
+}
+
+// Every corner between consecutive planar curves cut with a line set back by the distance
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Chamfer {
+    pub parts:    Vec<Model>,
+    pub reshape:  Reshape,
+    pub distance: Float,
+}
+
+impl Chamfer {
+    pub fn get_shapes(&self) -> Vec<Shape> {
+        let curves = get_cornered_curves(&self.parts, |a, b| CurveShape::chamfer(a, b, self.distance));
+        self.reshape.get_reshapes(curves.into_iter().map(Shape::Curve).collect())
+    }
+}
+
+// Curves are taken as one chain in order. Corners that do not fit are left sharp.
+fn get_cornered_curves<F>(parts: &Vec<Model>, corner: F) -> Vec<CurveShape>
+where F: Fn(&CurveShape, &CurveShape) -> Option<(CurveShape, CurveShape, CurveShape)> {
+    let mut pieces: Vec<CurveShape> = get_curves(parts).iter().flat_map(|curve| curve.get_smooth_pieces()).collect();
+    let mut corners: Vec<Option<CurveShape>> = vec![None; pieces.len()];
+    for i in 0..pieces.len() {
+        let j = (i + 1) % pieces.len();
+        if j == i {
+            continue;
+        }
+        if let Some((a, curve, b)) = corner(&pieces[i], &pieces[j]) {
+            pieces[i] = a;
+            pieces[j] = b;
+            corners[i] = Some(curve);
+        }
+    }
+    pieces.into_iter().zip(corners).flat_map(|(piece, corner)| [Some(piece), corner]).flatten().collect()
+}
@@ -0,0 +1,61 @@
+use crate::{get_points, CurveFit, CurveShape, Model, Parameterization, Shape};
+use serde::{Deserialize, Serialize};
+use crate::scalar::*;
+
+// Compact curve approximating dense points such as probe data or imported polylines
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Fit {
+    pub points:           Vec<Model>,
+    pub order:            usize,
+    pub parameterization: Parameterization,
+    pub tolerance:        Float,
+}
+
+impl Default for Fit {
+    fn default() -> Self {
+        Self {
+            points:           vec![],
+            order:            4,
+            parameterization: Parameterization::default(),
+            tolerance:        0.01,
+        }
+    }
+}
+
+impl Fit {
+    pub fn get_shapes(&self) -> Vec<Shape> {
+        self.get_fit().map(|(curve, _)| Shape::Curve(curve)).into_iter().collect()
+    }
+    // Deviation report of the fitted curve, which get_shapes leaves out
+    pub fn get_report(&self) -> Option<CurveFit> {
+        self.get_fit().map(|(_, report)| report)
+    }
+    fn get_fit(&self) -> Option<(CurveShape, CurveFit)> {
+        CurveShape::fit(&get_points(&self.points), self.order, self.parameterization, self.tolerance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scalar::*;
+    use crate::{Fit, Shape};
+
+    #[test]
+    fn report_describes_the_fitted_curve() {
+        let points: Vec<serde_json::Value> = (0..=40).map(|i| {
+            let x = i as Float / 10.;
+            serde_json::json!({"Point": [x, x.sin(), 0]})
+        }).collect();
+        let fit: Fit = serde_json::from_value(serde_json::json!({"points": points, "tolerance": 0.001})).unwrap();
+        let report = fit.get_report().unwrap();
+        let Some(Shape::Curve(curve)) = fit.get_shapes().pop() else {
+            panic!("fit without curve");
+        };
+        assert_eq!(report.control_count, curve.controls.len());
+        assert_eq!(report.deviations.len(), 41);
+        assert!(report.max_deviation <= 0.001);
+        assert!(report.mean_deviation <= report.max_deviation);
+        assert!(Fit::default().get_report().is_none());
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{get_shapes, get_reshapes, Reshape, Model, Shape};
 use serde::{Deserialize, Serialize};
-use glam::*;
+use crate::scalar::*;
 
 
 #[derive(Clone, Default, Serialize, Deserialize)]
@@ -9,13 +9,13 @@ pub struct GridPattern {
     pub parts:    Vec<Model>,
     pub reshape:  Reshape,
     pub count:    [usize; 3],
-    pub length:   [f32; 3],
+    pub length:   [Float; 3],
     pub x_count:  usize,
     pub y_count:  usize,
     pub z_count:  usize,
-    pub x_length: f32,
-    pub y_length: f32,
-    pub z_length: f32,
+    pub x_length: Float,
+    pub y_length: Float,
+    pub z_length: Float,
 }
 
 impl GridPattern {
@@ -52,16 +52,16 @@ impl GridPatternBasis {
     pub fn get_shapes(&self, parts: Vec<Shape>) -> Vec<Shape> {
         let mut shapes = vec![];
         let mut div = (1., 1., 1.);
-        if self.count[0] > 1 {div.0 = (self.count[0]-1) as f32;} 
-        if self.count[1] > 1 {div.1 = (self.count[1]-1) as f32;} 
-        if self.count[2] > 1 {div.2 = (self.count[2]-1) as f32;} 
+        if self.count[0] > 1 {div.0 = (self.count[0]-1) as Float;} 
+        if self.count[1] > 1 {div.1 = (self.count[1]-1) as Float;} 
+        if self.count[2] > 1 {div.2 = (self.count[2]-1) as Float;} 
         for x in 0..self.count[0] {
             for y in 0..self.count[1] {
                 for z in 0..self.count[2] {
                     let pos = vec3(
-                        (x as f32 / div.0) * self.length.x - self.length.x/2., 
-                        (y as f32 / div.1) * self.length.y - self.length.y/2., 
-                        (z as f32 / div.2) * self.length.z - self.length.z/2., 
+                        (x as Float / div.0) * self.length.x - self.length.x/2., 
+                        (y as Float / div.1) * self.length.y - self.length.y/2., 
+                        (z as Float / div.2) * self.length.z - self.length.z/2., 
                     );
                     let mat4 = Mat4::from_translation(pos);
                     shapes.extend(get_reshapes(&parts, mat4));
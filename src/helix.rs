@@ -0,0 +1,179 @@
+use crate::scalar::consts::{FRAC_PI_3, TAU};
+use crate::{Area, Curve, CurveShape, Cylinder, Model, Parameterization, Reshape, Shape, Sweep, SweepFrame, Union};
+use serde::{Deserialize, Serialize};
+use crate::scalar::*;
+
+const TURN_SAMPLES: usize = 32;
+// Part of the thread depth that reaches into the core
+const ROOT_SINK: Float = 0.25;
+
+// Helix about the Z axis starting at (radius, 0, 0) and rising by pitch each turn
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Helix {
+    pub radius:      Float,
+    pub pitch:       Float,
+    pub turns:       Float,
+    pub left_handed: bool,
+    pub reshape:     Reshape,
+}
+
+impl Default for Helix {
+    fn default() -> Self {
+        Self {
+            radius:      1.,
+            pitch:       1.,
+            turns:       1.,
+            left_handed: false,
+            reshape:     Reshape::default(),
+        }
+    }
+}
+
+impl Helix {
+    pub fn get_shapes(&self) -> Vec<Shape> {
+        let Some(mut curve) = CurveShape::helix(self.radius, self.pitch, self.turns) else {
+            return vec![];
+        };
+        if self.left_handed {
+            curve.reshape(Mat4::from_scale(vec3(1., -1., 1.)));
+        }
+        self.reshape.get_reshapes(vec![Shape::Curve(curve)])
+    }
+}
+
+impl CurveShape {
+    // Right-handed helix interpolated with exact end tangents.
+    // Thirty-two points per turn keep the radial error near 1e-5 of the radius.
+    pub fn helix(radius: Float, pitch: Float, turns: Float) -> Option<CurveShape> {
+        let count = ((turns.abs() * TURN_SAMPLES as Float).ceil() as usize).max(2);
+        let rise = pitch / TAU;
+        let angle = turns * TAU;
+        let points: Vec<Vec3> = (0..=count).map(|i| {
+            let theta = angle * i as Float / count as Float;
+            vec3(radius * theta.cos(), radius * theta.sin(), rise * theta)
+        }).collect();
+        let tangent = |theta: Float| vec3(-radius * theta.sin(), radius * theta.cos(), rise) * angle.signum();
+        CurveShape::interpolate(&points, 4, Parameterization::ChordLength, Some(tangent(0.)), Some(tangent(angle)))
+    }
+}
+
+// Cylinder core with a symmetric V thread screwed along its length.
+// The thread sits on the core at radius - depth and its crest reaches radius.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Thread {
+    pub radius:      Float,
+    pub length:      Float,
+    pub pitch:       Float,
+    pub depth:       Float,
+    pub angle:       Float,
+    pub left_handed: bool,
+    pub reshape:     Reshape,
+}
+
+impl Default for Thread {
+    fn default() -> Self {
+        Self {
+            radius:      5.,
+            length:      10.,
+            pitch:       1.,
+            depth:       0.6,
+            angle:       FRAC_PI_3,
+            left_handed: false,
+            reshape:     Reshape::default(),
+        }
+    }
+}
+
+impl Thread {
+    pub fn get_shapes(&self) -> Vec<Shape> {
+        // Flank run along the axis, limited so neighboring turns do not overlap
+        let run = (self.depth * (self.angle / 2.).tan()).min(self.pitch / 2.);
+        let crest = (self.pitch - run * 2.) / 2.;
+        let base = crest + run * 2.;
+        let minor = self.radius - self.depth;
+        let core = Cylinder {
+            radius: minor,
+            length: self.length,
+            ..Default::default()
+        };
+        let turns = (self.length - base) / self.pitch;
+        if turns <= 0. || self.depth <= 0. || minor <= 0. {
+            return self.reshape.get_reshapes(core.get_shapes());
+        }
+        // Frenet frames of the helix turn profile X toward the axis and profile Y nearly along it.
+        // Y shrinks by the cosine of the lead angle so the section in the axial plane keeps its shape.
+        // The root sinks into the core so the union has no coincident faces.
+        let lead = (self.pitch / (TAU * minor)).atan();
+        let sink = self.depth * ROOT_SINK;
+        let corners = [
+            vec2(sink, base / 2.),
+            vec2(0., base / 2.),
+            vec2(-self.depth, crest / 2.),
+            vec2(-self.depth, -crest / 2.),
+            vec2(0., -base / 2.),
+            vec2(sink, -base / 2.),
+        ].map(|corner| vec3(corner.x, corner.y * lead.cos(), 0.));
+        let profile = Area::from_parts((0..corners.len()).map(|i| Model::Curve(Curve {
+            controls: vec![Model::Point(corners[i]), Model::Point(corners[(i + 1) % corners.len()])],
+            ..Default::default()
+        })).collect());
+        let helix = Helix {
+            radius:      minor,
+            pitch:       self.pitch,
+            turns,
+            left_handed: self.left_handed,
+            reshape:     Reshape {
+                position: vec3(0., 0., base / 2.),
+                ..Default::default()
+            },
+        };
+        let thread = Sweep {
+            parts: vec![Model::Area(profile)],
+            path:  vec![Model::Helix(helix)],
+            frame: SweepFrame::Frenet,
+            ..Default::default()
+        };
+        self.reshape.get_reshapes(Union {
+            parts: vec![Model::Cylinder(core), Model::Sweep(thread)],
+            ..Default::default()
+        }.get_shapes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scalar::*;
+    use crate::{Helix, Shape};
+
+    fn get_points(helix: &Helix) -> Vec<Vec3> {
+        let Shape::Curve(curve) = helix.get_shapes().remove(0) else {
+            panic!("helix without curve");
+        };
+        (0..=100).map(|i| curve.get_point(i as Float / 100.)).collect()
+    }
+
+    #[test]
+    fn helix_keeps_radius_and_rises_by_pitch() {
+        let helix = Helix {radius: 3., pitch: 2., turns: 2.5, ..Default::default()};
+        let points = get_points(&helix);
+        for point in &points {
+            // Height gives the angle, so the point must sit on the helix at that angle
+            let theta = point.z * consts::TAU / helix.pitch;
+            assert!(point.distance(vec3(3. * theta.cos(), 3. * theta.sin(), point.z)) < 1e-4);
+        }
+        assert!(points[0].distance(vec3(3., 0., 0.)) < 1e-4);
+        assert!(points[100].distance(vec3(-3., 0., 5.)) < 1e-4);
+    }
+
+    #[test]
+    fn left_handed_helix_turns_the_other_way() {
+        let right = get_points(&Helix::default());
+        let left = get_points(&Helix {left_handed: true, ..Default::default()});
+        for (a, b) in right.iter().zip(&left) {
+            assert!(a.distance(vec3(b.x, -b.y, b.z)) < 1e-5);
+        }
+        assert!(left[10].y < 0.);
+    }
+}
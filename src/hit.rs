@@ -1,4 +1,4 @@
-use glam::Vec3;
+use crate::scalar::Float;
 
 pub mod hit2;
 pub mod hit3;
@@ -17,14 +17,14 @@ pub struct IndexPair {
 
 #[derive(Clone)]
 pub struct Miss {
-    pub distance: f32,
-    pub dot: f32,
+    pub distance: Float,
+    pub dot: Float,
 }
 
 #[derive(Clone)]
 pub struct MissPair {
     pub index: IndexPair,
-    pub distance: f32,
-    pub dot0: f32,
-    pub dot1: f32,
+    pub distance: Float,
+    pub dot0: Float,
+    pub dot1: Float,
 }
\ No newline at end of file
@@ -1,4 +1,4 @@
-use glam::*;
+use crate::scalar::*;
 use crate::{log, FacetShape};
 use super::{IndexPair, MissPair};
 
@@ -10,6 +10,10 @@ struct IndexedUV {
 
 
 
+// Highest order the hone shader evaluates, the same as MAX_ORDER in shader_parts.rs.
+// Facets with a higher order are packed but not paired, so they are left out of hit testing.
+pub const MAX_ORDER: usize = 8;
+
 #[derive(Default)]
 pub struct HoneBasis{
     pub index_pairs: Vec<IndexPair>,
@@ -36,13 +40,15 @@ impl HoneBasis {
                     max_knot_count = facet.nurbs.knots.len();
                 }
                 let texel_i = facet_texels.len();
+                let supported = facet.nurbs.order <= MAX_ORDER
+                    && facet.controls.iter().all(|curve| curve.nurbs.order <= MAX_ORDER);
                 facet_texels.extend([
-                    facet.nurbs.sign, //10000000.,
+                    to_f32(facet.nurbs.sign), //10000000.,
                     facet.controls.len() as f32,
                     facet.nurbs.order as f32,
                 ]);
-                facet_texels.extend(&facet.nurbs.knots);
-                facet_texels.extend(&facet.nurbs.weights);
+                facet_texels.extend(facet.nurbs.knots.iter().map(|k| to_f32(*k)));
+                facet_texels.extend(facet.nurbs.weights.iter().map(|w| to_f32(*w)));
                 for (ci, curve) in facet.controls.iter().enumerate() {
                     if curve.nurbs.knots.len() > max_knot_count { 
                         max_knot_count = curve.nurbs.knots.len(); 
@@ -51,20 +57,20 @@ impl HoneBasis {
                         9000000. + ci as f32,
                         curve.controls.len() as f32,
                         curve.nurbs.order as f32,
-                        curve.min,
-                        curve.max,
+                        to_f32(curve.min),
+                        to_f32(curve.max),
                     ]); 
                     for i in 0..curve.nurbs.knots.len()-1 {
-                        if curve.nurbs.knots[i] < curve.nurbs.knots[i+1] || i == curve.nurbs.knots.len() - curve.nurbs.order {
+                        if supported && (curve.nurbs.knots[i] < curve.nurbs.knots[i+1] || i == curve.nurbs.knots.len() - curve.nurbs.order) {
                             indexed_uvs.push(IndexedUV{
-                                facet_i, texel_i, uv:vec2(curve.nurbs.knots[i], ci as f32 / (facet.controls.len()-1) as f32)}); 
+                                facet_i, texel_i, uv:vec2(curve.nurbs.knots[i], ci as Float / (facet.controls.len()-1) as Float)}); 
                         }
-                        facet_texels.push(curve.nurbs.knots[i]);
+                        facet_texels.push(to_f32(curve.nurbs.knots[i]));
                     }  
-                    facet_texels.push(curve.nurbs.knots[curve.nurbs.knots.len()-1]);
-                    facet_texels.extend(&curve.nurbs.weights);
+                    facet_texels.push(to_f32(curve.nurbs.knots[curve.nurbs.knots.len()-1]));
+                    facet_texels.extend(curve.nurbs.weights.iter().map(|w| to_f32(*w)));
                     for point in &curve.controls {
-                        facet_texels.extend(point.to_array());
+                        facet_texels.extend(point.to_array().map(to_f32));
                     }
                 }
                 let facet_length = facet_texels.len() - texel_i;
@@ -81,8 +87,8 @@ impl HoneBasis {
                         index_pairs.push(IndexPair{g0, g1, i0:*f0, i1:*f1});
                         pair_texels.push(*t0 as i32);
                         pair_texels.push(*t1 as i32);
-                        uv_texels.extend(uv0.to_array());
-                        uv_texels.extend(uv1.to_array());
+                        uv_texels.extend(uv0.to_array().map(to_f32));
+                        uv_texels.extend(uv1.to_array().map(to_f32));
                     }  
                 }   
             }
@@ -136,9 +142,9 @@ impl TraceBasis {
                     // }
                     misses.push(MissPair { 
                         index:    basis.index_pairs[i].clone(),
-                        distance: hit_miss[i*4+1],
-                        dot0:     hit_miss[i*4+2], 
-                        dot1:     hit_miss[i*4+3], 
+                        distance: hit_miss[i*4+1] as Float,
+                        dot0:     hit_miss[i*4+2] as Float, 
+                        dot1:     hit_miss[i*4+3] as Float, 
                     });
                 }
             }
@@ -161,4 +167,32 @@ impl TraceBasis {
 //     ...group_facet_indices0[i],
 //     uv0: [hit_miss[i*4+0], hit_miss[i*4+1]],
 //     uv1: [hit_miss[i*4+2], hit_miss[i*4+3]],
-// });
\ No newline at end of file
+// });
+#[cfg(test)]
+mod tests {
+    use crate::scalar::*;
+    use crate::{nurbs::Nurbs, CurveShape, FacetShape};
+    use super::{HoneBasis, MAX_ORDER};
+
+    fn get_facet(order: usize) -> FacetShape {
+        let curve = |y: Float| CurveShape {
+            controls: (0..order).map(|i| vec3(i as Float, y, 0.)).collect(),
+            nurbs: Nurbs {order, ..Default::default()},
+            ..Default::default()
+        };
+        FacetShape {
+            controls: vec![curve(0.), curve(1.)],
+            ..Default::default()
+        }.get_valid()
+    }
+
+    #[test]
+    fn orders_past_the_shader_limit_are_not_paired() {
+        let basis = HoneBasis::new(&vec![vec![get_facet(2)], vec![get_facet(MAX_ORDER)]]);
+        assert!(!basis.index_pairs.is_empty());
+        let basis = HoneBasis::new(&vec![vec![get_facet(2)], vec![get_facet(MAX_ORDER + 1)]]);
+        assert!(basis.index_pairs.is_empty());
+        // The facet is still packed so texel offsets of later facets do not move
+        assert!(basis.facet_texels.contains(&((MAX_ORDER + 1) as f32)));
+    }
+}
@@ -1,5 +1,5 @@
-use std::f32::{EPSILON, INFINITY};
-use glam::*;
+use crate::scalar::EPSILON;
+use crate::scalar::*;
 use crate::arrow::*;
 use crate::{log, CurveShape, Spatial3};
 
@@ -10,8 +10,8 @@ pub struct HitTester2 {
     pub curves: (CurveShape, CurveShape),
     pub spatial:      Spatial3,
     pub points:       Vec<Vec3>,
-    pub tolerance:    f32,
-    pub duplication_tolerance: f32,
+    pub tolerance:    Float,
+    pub duplication_tolerance: Float,
 }
 
 #[derive(Clone)]
@@ -22,12 +22,12 @@ pub struct Hit2 {
 
 #[derive(Clone)]
 pub struct CurveHit {
-    pub u: f32,
-    pub dot: f32,
+    pub u: Float,
+    pub dot: Float,
 }
 
 impl HitTester2 { 
-    pub fn test(&mut self, start_u0: f32, start_u1: f32) -> Result<Hit2, (Miss, Miss)> { 
+    pub fn test(&mut self, start_u0: Float, start_u1: Float) -> Result<Hit2, (Miss, Miss)> { 
         let mut u0 = start_u0;
         let mut u1 = start_u1;
         let mut p0 = self.curves.0.get_point(u0);
@@ -149,7 +149,7 @@ impl HitTester2 {
         ))
     }
 
-    pub fn get_tangent_intersection(&self, u0: f32, u1: f32, p0: Vec3, p1: Vec3) -> Vec3 {
+    pub fn get_tangent_intersection(&self, u0: Float, u1: Float, p0: Vec3, p1: Vec3) -> Vec3 {
         // let ray0 = Ray::new(p0, self.curves.0.get_tangent_at_u(u0));
         // let ray1 = Ray::new(p1, self.curves.1.get_tangent_at_u(u1));
         let ray0 = self.curves.0.get_arrow(u0);
@@ -1,9 +1,9 @@
 use crate::{log, CurveShape, FacetShape, Shape};
 use crate::gpu::{framebuffer::Framebuffer, shader::COPY_FRAGMENT_SOURCE, GPU};
-use glam::*;
+use crate::scalar::*;
 use web_sys::WebGlProgram;
 use super::basis3::{HoneBasis, TraceBasis};
-use super::traced::{get_traced_curves, TracedCurve};
+use super::traced::{get_traced_curves, simplify_traced_curves, TracedCurve};
 use super::{IndexPair, Miss, MissPair};
 use super::shader::{
     INIT_HONE_PALETTE_SOURCE, HONE_PALETTE_SOURCE, HIT_MISS_SOURCE, 
@@ -27,8 +27,8 @@ struct TraceBuffer {
 //#[derive(Clone)]
 pub struct HitBasis3 {
     pub facet_groups: Vec<Vec<FacetShape>>,
-    pub tolerance: f32,
-    pub step:      f32,
+    pub tolerance: Float,
+    pub step:      Float,
     pub length:    usize,
     pub facet_hits: Vec<Vec<Vec<Vec<CurveShape>>>>, 
     pub facet_miss: Vec<Vec<Vec<Vec<Miss>>>>, 
@@ -134,12 +134,13 @@ impl HitBasis3 {
         });
         self.trace(trace_length);
         let buff1   = &self.trace_buffer.as_ref().unwrap();
-        let boxes      = self.gpu.read(&buff1.boxes, 0);
-        let origins    = self.gpu.read(&buff1.trace, 0);
-        let vectors    = self.gpu.read(&buff1.trace, 1);
-        let uvs        = self.gpu.read(&buff1.trace, 2);
-        let uv_vectors = self.gpu.read(&buff1.trace, 3);
-        let traced_curves = get_traced_curves(trace_basis.index_pairs, trace_buf_size, uvs, boxes, origins, uv_vectors, vectors);
+        let boxes      = from_f32(self.gpu.read(&buff1.boxes, 0));
+        let origins    = from_f32(self.gpu.read(&buff1.trace, 0));
+        let vectors    = from_f32(self.gpu.read(&buff1.trace, 1));
+        let uvs        = from_f32(self.gpu.read(&buff1.trace, 2));
+        let uv_vectors = from_f32(self.gpu.read(&buff1.trace, 3));
+        let mut traced_curves = get_traced_curves(trace_basis.index_pairs, trace_buf_size, uvs, boxes, origins, uv_vectors, vectors);
+        simplify_traced_curves(&mut traced_curves, &self.facet_groups, self.tolerance);
         for TracedCurve{index_pair, curve0, curve1, center} in traced_curves {
             let IndexPair{g0, g1, i0, i1} = index_pair;
             self.facet_hits[g0][i0][g1-g0-1].push(curve0);
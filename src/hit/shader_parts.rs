@@ -48,53 +48,57 @@ int get_knot_index(int idx, int knot_count, int order, float u){
     return knot_count - order - 1;
 }
 
-float[8] get_basis(int ki, int order, int control_len, float u){
-    float k0  = get_facet_texel(ki);
-    float k1  = get_facet_texel(ki + 1);
-    float k1u  = k1 - u;
-    float uk0  = u - k0;
-    float k0k1 = k0 - k1;
-    float k1k0 = k1 - k0;
-    float k1u_d_k1k0 = k1u / k1k0;
-    float uk0_d_k1k0 = uk0 / k1k0;
-    if(order > 2){ // quadratic
-        float r1 = get_facet_texel(ki - 1);
-        float k2 = get_facet_texel(ki + 2);
-        float w0 = get_facet_texel(ki + control_len + 1);
-        float w1 = get_facet_texel(ki + control_len + 2);
-        float w2 = get_facet_texel(ki + control_len + 3);
-            // origin parts:
-        float k0u = k0 - u;
-        float k2u = k2 - u;
-        float ur1 = u - r1;
-        float r1k2 = r1 - k2;
-        float k0k2 = k0 - k2;
-        float k1r1 = k1 - r1;
-        float k2k0 = k2 - k0;
-        float w0xk1u = w0 * k1u;
-        float w2xuk0 = w2 * uk0;
-        float p0 = w0xk1u * k1u_d_k1k0 / k1r1;
-        float p1 = (k1u_d_k1k0 * ur1/k1r1 + uk0_d_k1k0 * k2u/k2k0) * w1;
-        float p2 = w2xuk0 * uk0_d_k1k0 / k2k0;
-        float sum = p0 + p1 + p2;
-            // derivative parts:
-        float a0 = 2. * k0k1 * k0k2 * k1r1;
-        float n0 = a0 * w0xk1u * (w1 * (u-k2) - w2xuk0);
-        float n1 = a0 * w1 * (w0 * k1u * k2u - w2xuk0 * ur1);
-        float n2 = a0 * w2xuk0 * (w0 * k1u + w1 * ur1);
-        float uxu = u * u;
-        float k2xr1 = k2 * r1;
-        float ux2 = u * 2.;
-        float a1 = - w0xk1u * k0k2 * k1u + w1 * (k0 * (k1 * r1k2 + k2xr1 - r1 * ux2 + uxu) - k1*(k2xr1 - k2 * ux2 + uxu) + uxu * r1k2);
-        float d0 = a1 + w2xuk0 * uk0 * k1r1;
-        float d1 = a1 + w2 * k0u * k0u * k1r1;
-        return float[8](0., p0/sum, p1/sum, p2/sum, 0., n0/d0/d0, n1/d0/d0, n2/d1/d1);
-    } else { // linear
-        return float[8](0., 0., k1u_d_k1k0, uk0_d_k1k0, 0., 0., 1./k0k1, 1./k1k0);
+// Same as MAX_ORDER in basis3.rs, which keeps higher orders out of the hone pairs
+const int MAX_ORDER = 8;
+
+float[MAX_ORDER*2] get_basis(int ki, int order, int control_len, float u){
+    int degree = order - 1;
+    float basis[MAX_ORDER];
+    float lower[MAX_ORDER];
+    float left[MAX_ORDER];
+    float right[MAX_ORDER];
+    float[MAX_ORDER*2] result;
+    for(int i = 0; i < MAX_ORDER; i++){
+        basis[i] = 0.;
+        result[i] = 0.;
+        result[MAX_ORDER + i] = 0.;
     }
+    basis[0] = 1.;
+    lower = basis;
+        // Cox-de Boor triangle:
+    for(int j = 1; j < order; j++){
+        left[j]  = u - get_facet_texel(ki + 1 - j);
+        right[j] = get_facet_texel(ki + j) - u;
+        float saved = 0.;
+        for(int r = 0; r < j; r++){
+            float temp = basis[r] / (right[r+1] + left[j-r]);
+            basis[r] = saved + right[r+1] * temp;
+            saved = left[j-r] * temp;
+        }
+        basis[j] = saved;
+        if(j == degree - 1){
+            lower = basis;
+        }
+    }
+        // derivative parts times weights for homogeneous space:
+    for(int k = 0; k < order; k++){
+        float d = 0.;
+        if(k > 0){
+            float span = get_facet_texel(ki + k) - get_facet_texel(ki + k - degree);
+            if(span > 0.) d += lower[k-1] / span;
+        }
+        if(k < degree){
+            float span = get_facet_texel(ki + k + 1) - get_facet_texel(ki + k + 1 - degree);
+            if(span > 0.) d -= lower[k] / span;
+        }
+        float w = get_facet_texel(ki + control_len + 1 + k);
+        result[k] = basis[k] * w;
+        result[MAX_ORDER + k] = d * float(degree) * w;
+    }
+    return result;
 }
 
-float[6] get_curve_arrow(int idx, int nth, float u){
+float[8] get_curve_arrow(int idx, int nth, float u){
     int ci = get_curve_index(idx, nth);
     int control_count = int(get_facet_texel(ci + 1));
     int order = int(get_facet_texel(ci + 2));
@@ -105,14 +109,16 @@ float[6] get_curve_arrow(int idx, int nth, float u){
     float velocity_scale = max - min;
     int knot_i = get_knot_index(ci + 5, knot_count, order, u);
     int control_start = ci + 5 + knot_count + control_count + (knot_i-order+1)*3;
-    float[8] basis = get_basis(ci + 5 + knot_i, order, control_count, u);
-    float[6] arrow = float[6](0., 0., 0., 0., 0., 0.);
+    float[MAX_ORDER*2] basis = get_basis(ci + 5 + knot_i, order, control_count, u);
+    float[8] arrow = float[8](0., 0., 0., 0., 0., 0., 0., 0.);
     for(int k = 0; k < order; k++) {
         for(int j = 0; j < 3; j++) {
             float control_component = get_facet_texel(control_start + k*3 + j);
-            arrow[j]   += control_component * basis[4-order+k];
-            arrow[j+3] += control_component * basis[8-order+k] * velocity_scale;
+            arrow[j]   += control_component * basis[k];
+            arrow[j+4] += control_component * basis[MAX_ORDER+k] * velocity_scale;
         }
+        arrow[3] += basis[k];
+        arrow[7] += basis[MAX_ORDER+k] * velocity_scale;
     }
     return arrow; 
 }
@@ -123,17 +129,26 @@ float[9] get_facet_arrows(int fi, vec2 uv){
     int knot_count = control_count + order;
     int knot_i = get_knot_index(fi + 3, knot_count, order, uv.y);
     int nth_control = knot_i - order + 1;
-    float[8] basis = get_basis(fi + 3 + knot_i, order, control_count, uv.y);
-    float[9] arrows = float[9](0., 0., 0., 0., 0., 0., 0., 0., 0.);
+    float[MAX_ORDER*2] basis = get_basis(fi + 3 + knot_i, order, control_count, uv.y);
+    vec4 point = vec4(0., 0., 0., 0.);
+    vec4 du = vec4(0., 0., 0., 0.);
+    vec4 dv = vec4(0., 0., 0., 0.);
     for(int k = 0; k < order; k++) {
-        float[6] arrow = get_curve_arrow(fi, nth_control + k, uv.x); 
-        for(int j = 0; j < 3; j++) {
-            arrows[j]   += arrow[j]   * basis[4-order+k];
-            arrows[j+3] += arrow[j+3] * basis[4-order+k];
-            arrows[j+6] += arrow[j]   * basis[8-order+k];
-        }
+        float[8] arrow = get_curve_arrow(fi, nth_control + k, uv.x); 
+        vec4 p = vec4(arrow[0], arrow[1], arrow[2], arrow[3]);
+        point += p * basis[k];
+        du += vec4(arrow[4], arrow[5], arrow[6], arrow[7]) * basis[k];
+        dv += p * basis[MAX_ORDER+k];
     }
-    return arrows; 
+        // project from homogeneous space:
+    vec3 origin = point.xyz / point.w;
+    vec3 vector_u = (du.xyz - origin * du.w) / point.w;
+    vec3 vector_v = (dv.xyz - origin * dv.w) / point.w;
+    return float[9](
+        origin.x, origin.y, origin.z, 
+        vector_u.x, vector_u.y, vector_u.z, 
+        vector_v.x, vector_v.y, vector_v.z
+    ); 
 }
 "##;
 
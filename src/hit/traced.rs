@@ -1,9 +1,12 @@
-use glam::*;
+use crate::scalar::*;
 use crate::arrow::*;
-use crate::{log, CurveShape};
+use crate::{CurveShape, FacetShape};
 use super::IndexPair;
 use std::collections::HashMap;
 
+// Grid of uv samples per direction that bounds how fast a facet moves in 3d
+const SPEED_SAMPLES: usize = 8;
+
 pub struct TracedCurve {
     pub index_pair: IndexPair,
     pub curve0: CurveShape,
@@ -13,8 +16,8 @@ pub struct TracedCurve {
 
 //impl TracedCurve {
 pub fn get_traced_curves(
-    index_pairs: Vec<IndexPair>, buf_size: IVec2, traces: Vec<f32>, boxes: Vec<f32>, 
-    centers0: Vec<f32>, uv_dirs: Vec<f32>, dirs: Vec<f32>, 
+    index_pairs: Vec<IndexPair>, buf_size: IVec2, traces: Vec<Float>, boxes: Vec<Float>, 
+    centers0: Vec<Float>, uv_dirs: Vec<Float>, dirs: Vec<Float>, 
 ) -> Vec<TracedCurve> {
     let mut traced_curves = vec![];
     let mut box_map: HashMap<String, Vec<(Vec2, Vec2)>> = HashMap::new();
@@ -118,16 +121,7 @@ pub fn get_traced_curves(
         //         log("double point on rays2a!!!");
         //     }
         // }
-        if rays0a.len() < 3 {
-            console_log!("rays0: {}", rays0a.len());
-            continue;
-        }
-        if rays1a.len() < 3 {
-            console_log!("rays1: {}", rays1a.len());
-            continue;
-        }
-        if rays2a.len() < 3 {
-            console_log!("rays2: {}", rays2a.len());
+        if rays0a.len() < 3 || rays1a.len() < 3 || rays2a.len() < 3 {
             continue;
         }
         //if duplicate {continue}
@@ -185,4 +179,29 @@ pub fn get_traced_curves(
         });
     }
     traced_curves
-}
\ No newline at end of file
+}
+
+// Remove knots while the curves stay within tolerance in 3d. The uv curves get the tolerance of their facet
+// in uv, and all three are simplified together so they keep the shared knot vector that trimming relies on.
+pub fn simplify_traced_curves(traced_curves: &mut [TracedCurve], facet_groups: &[Vec<FacetShape>], tolerance: Float) {
+    let uv_tolerances: Vec<Vec<Float>> = facet_groups.iter()
+        .map(|group| group.iter().map(|facet| get_uv_tolerance(facet, tolerance)).collect()).collect();
+    for traced in traced_curves {
+        let IndexPair{g0, g1, i0, i1} = traced.index_pair;
+        let mut curves = [traced.curve0.clone(), traced.curve1.clone(), traced.center.clone()];
+        CurveShape::simplify_together(&mut curves, &[uv_tolerances[g0][i0], uv_tolerances[g1][i1], tolerance]);
+        [traced.curve0, traced.curve1, traced.center] = curves;
+    }
+}
+
+// Largest uv move that stays within tolerance in 3d, from the fastest sampled partial derivatives
+fn get_uv_tolerance(facet: &FacetShape, tolerance: Float) -> Float {
+    let mut speed: Float = 0.;
+    for i in 0..=SPEED_SAMPLES {
+        for j in 0..=SPEED_SAMPLES {
+            let uv = vec2(i as Float, j as Float) / SPEED_SAMPLES as Float;
+            speed = speed.max(facet.get_derivative_u(uv).length() + facet.get_derivative_v(uv).length());
+        }
+    }
+    if speed > 0. {tolerance / speed} else {tolerance}
+}
@@ -0,0 +1,38 @@
+use crate::{get_points, CurveShape, Model, Parameterization, Shape};
+use serde::{Deserialize, Serialize};
+use crate::scalar::*;
+
+// Curve passing through the points rather than treating them as controls
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Interpolate {
+    pub points:           Vec<Model>,
+    pub order:            usize,
+    pub parameterization: Parameterization,
+    pub start_tangent:    Option<Vec3>,
+    pub end_tangent:      Option<Vec3>,
+}
+
+impl Default for Interpolate {
+    fn default() -> Self {
+        Self {
+            points:           vec![],
+            order:            4,
+            parameterization: Parameterization::default(),
+            start_tangent:    None,
+            end_tangent:      None,
+        }
+    }
+}
+
+impl Interpolate {
+    pub fn get_shapes(&self) -> Vec<Shape> {
+        CurveShape::interpolate(
+            &get_points(&self.points),
+            self.order,
+            self.parameterization,
+            self.start_tangent,
+            self.end_tangent,
+        ).map(Shape::Curve).into_iter().collect()
+    }
+}
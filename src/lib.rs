@@ -10,11 +10,13 @@ extern "C" {
     fn log(s: &str);
 }
 
+#[allow(unused_macros)]
 macro_rules! console_log {
     ($($t:tt)*) => (log(&format_args!($($t)*).to_string()))
 }
 
 mod utils;
+mod scalar;
 mod query;
 mod scene;
 mod gpu;
@@ -32,6 +34,16 @@ mod grid_pattern;
 mod radial_pattern;
 mod mirror;
 mod arrow;
+mod bounds;
+mod interpolate;
+mod fit;
+mod loft;
+mod sweep;
+mod helix;
+mod offset;
+mod fillet;
+mod conic;
+mod path;
 
 use utils::*;
 use nurbs::{curve::*, facet::*};
@@ -47,10 +59,20 @@ use revolve::*;
 use grid_pattern::*;
 use radial_pattern::*;
 use mirror::*;
+use bounds::*;
+use interpolate::*;
+use fit::*;
+use loft::*;
+use sweep::*;
+use helix::*;
+use offset::*;
+use fillet::*;
+use conic::*;
+use path::*;
 
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
-use glam::*;
+use scalar::*;
 
 
 //use crate::hit::FacetHit;
@@ -67,6 +89,8 @@ pub enum Model {
     Circle(Circle),
     Rectangle(Rectangle),
     Slot(Slot),
+    Polygon(Polygon),
+    #[serde(alias = "Extrusion")]
     Extrude(Extrude),
     Cuboid(Cuboid),
     Cylinder(Cylinder),
@@ -75,6 +99,23 @@ pub enum Model {
     GridPattern(GridPattern),
     RadialPattern(RadialPattern),
     Mirror(Mirror),
+    Interpolate(Interpolate),
+    Fit(Fit),
+    Loft(Loft),
+    Sweep(Sweep),
+    Helix(Helix),
+    Thread(Thread),
+    Offset(Offset),
+    Fillet(Fillet),
+    Chamfer(Chamfer),
+    Ellipse(Ellipse),
+    Parabola(Parabola),
+    Hyperbola(Hyperbola),
+    Path(Path),
+    Group(Group),
+    MoveTo(PathValue),
+    LineTo(PathValue),
+    ArcTo(ArcTo),
 }
 
 impl Model {
@@ -88,6 +129,7 @@ impl Model {
             Model::Circle(m)    => m.get_shapes(),
             Model::Rectangle(m) => m.get_shapes(),
             Model::Slot(m)      => m.get_shapes(),
+            Model::Polygon(m)   => m.get_shapes(),
             Model::Reshape(m)   => m.get_shapes(),
             Model::Area(m)      => m.get_shapes(),
             Model::Extrude(m)   => m.get_shapes(),
@@ -98,10 +140,41 @@ impl Model {
             Model::GridPattern(m)   => m.get_shapes(),
             Model::RadialPattern(m) => m.get_shapes(),
             Model::Mirror(m)        => m.get_shapes(),
+            Model::Interpolate(m)   => m.get_shapes(),
+            Model::Fit(m)           => m.get_shapes(),
+            Model::Loft(m)          => m.get_shapes(),
+            Model::Sweep(m)         => m.get_shapes(),
+            Model::Helix(m)         => m.get_shapes(),
+            Model::Thread(m)        => m.get_shapes(),
+            Model::Offset(m)        => m.get_shapes(),
+            Model::Fillet(m)        => m.get_shapes(),
+            Model::Chamfer(m)       => m.get_shapes(),
+            Model::Ellipse(m)       => m.get_shapes(),
+            Model::Parabola(m)      => m.get_shapes(),
+            Model::Hyperbola(m)     => m.get_shapes(),
+            Model::Path(m)          => m.get_shapes(),
+            Model::Group(m)         => m.get_shapes(),
+            Model::MoveTo(m)        => vec![Shape::Point(m.get_vec3_or(Vec3::ZERO))],
+            Model::LineTo(m)        => vec![Shape::Point(m.get_vec3_or(Vec3::ZERO))],
+            Model::ArcTo(m)         => m.get_shapes(),
         }
     }
 }
 
+impl Model {
+    pub fn get_box3(&self) -> Box3 {
+        Box3::from_points(&self.get_hull())
+    }
+
+    pub fn get_oriented_box3(&self) -> OrientedBox3 {
+        OrientedBox3::from_points(&self.get_hull())
+    }
+
+    fn get_hull(&self) -> Vec<Vec3> {
+        self.get_shapes().iter().flat_map(|shape| shape.get_hull()).collect()
+    }
+}
+
 impl Default for Model {
     fn default() -> Self { 
         Model::Point(Vec3::ZERO) // [0.; 3] 
@@ -116,6 +189,14 @@ pub enum Shape {
 }
 
 impl Shape {
+    // Control points enclosing the shape
+    pub fn get_hull(&self) -> Vec<Vec3> {
+        match self {
+            Shape::Point(s) => vec![*s],
+            Shape::Curve(s) => s.get_hull(),
+            Shape::Facet(s) => s.get_hull(),
+        }
+    }
     pub fn get_reshape(&self, mat4: Mat4) -> Self {
         match self {
             Shape::Point(s) => Shape::Point(get_reshaped_point(s, mat4)),
@@ -242,7 +323,7 @@ pub fn get_line_intersection2(p1: Vec2, p2: Vec2, p3: Vec2, p4: Vec2) -> Option<
     Some(vec2(x, y))
 }
 
-pub fn get_vector_hash(vecf32: &Vec<f32>) -> u64 {
+pub fn get_vector_hash(vecf32: &Vec<Float>) -> u64 {
     let veci32: Vec<u64> = vecf32.iter().enumerate().map(|(i, v)| i as u64 * (v * 10000.).floor() as u64).collect();
     veci32.iter().sum()
     // let mut hasher = DefaultHasher::new();
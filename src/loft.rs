@@ -0,0 +1,35 @@
+use crate::{get_curves, FacetShape, Model, Parameterization, Shape};
+use serde::{Deserialize, Serialize};
+
+// Surface passing through section curves in order
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Loft {
+    pub sections:         Vec<Model>,
+    pub order:            usize,
+    pub parameterization: Parameterization,
+    pub control_count:    usize, // 0 interpolates the sections
+    pub closed:           bool,
+}
+
+impl Default for Loft {
+    fn default() -> Self {
+        Self {
+            sections:         vec![],
+            order:            4,
+            parameterization: Parameterization::default(),
+            control_count:    0,
+            closed:           false,
+        }
+    }
+}
+
+impl Loft {
+    pub fn get_shapes(&self) -> Vec<Shape> {
+        let sections = get_curves(&self.sections);
+        if sections.len() < 2 {
+            return vec![];
+        }
+        vec![Shape::Facet(FacetShape::loft(&sections, self.order, self.parameterization, self.control_count, self.closed))]
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{Reshape, Model, Shape};
 use serde::{Deserialize, Serialize};
-use glam::*;
+use crate::scalar::*;
 
 
 #[derive(Clone, Default, Serialize, Deserialize)]
@@ -8,7 +8,7 @@ use glam::*;
 pub struct Mirror {
     pub parts:   Vec<Model>,
     pub reshape: Reshape,
-    pub axis:    [f32; 3], 
+    pub axis:    [Float; 3], 
 }
 
 impl Mirror {
@@ -1,16 +1,17 @@
 pub mod curve;
 pub mod facet;
+mod solve;
 
-use glam::*;
+use crate::scalar::*;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct Nurbs {
-    pub sign:     f32,
+    pub sign:     Float,
     pub order:    usize,       // order = polynomial_degree + 1
-    pub knots:    Vec<f32>,    // knot_count = order + control_count
-    pub weights:  Vec<f32>,    // weight_count = control_count
+    pub knots:    Vec<Float>,    // knot_count = order + control_count
+    pub weights:  Vec<Float>,    // weight_count = control_count
 }
 
 impl Default for Nurbs {
@@ -42,7 +43,7 @@ impl Nurbs {
         }
     }
 
-    fn get_valid_weights(&self, control_count: usize) -> Vec<f32> {
+    fn get_valid_weights(&self, control_count: usize) -> Vec<Float> {
         if self.weights.len() == control_count {
             self.weights.clone()
         } else {
@@ -50,7 +51,7 @@ impl Nurbs {
         }
     }
 
-    fn get_valid_knots(&self, control_count: usize, order: usize) -> Vec<f32> {
+    fn get_valid_knots(&self, control_count: usize, order: usize) -> Vec<Float> {
         if self.knots.len() == control_count + order { 
             let last_knot = self.knots.last().unwrap();
             self.knots.iter().map(|k| k / last_knot).collect()
@@ -60,22 +61,23 @@ impl Nurbs {
     }
     
     pub fn normalize_knots(&mut self) {
-        let last_knot = self.knots.last().unwrap();
-        self.knots = self.knots.iter().map(|k| k / last_knot).collect();
+        let first_knot = self.knots[0];
+        let span = self.knots.last().unwrap() - first_knot;
+        self.knots = self.knots.iter().map(|k| (k - first_knot) / span).collect();
     }
 
-    fn get_open_knots(&self, control_count: usize, order: usize) -> Vec<f32> {
+    fn get_open_knots(&self, control_count: usize, order: usize) -> Vec<Float> {
         let repeats = order - 1; // knot multiplicity = order for ends of knot vector
         let max_knot = control_count + order - (repeats * 2) - 1;
-        let mut knots = vec![0_f32; repeats];
-        knots.extend((0..=max_knot).map(|k| k as f32));
-        knots.extend(vec![max_knot as f32; repeats]);
+        let mut knots = vec![0. as Float; repeats];
+        knots.extend((0..=max_knot).map(|k| k as Float));
+        knots.extend(vec![max_knot as Float; repeats]);
         let last_knot = knots.last().unwrap();
         knots = knots.iter().map(|k| k / last_knot).collect();
         knots
     }
 
-    fn get_knot_index(&self, u: f32) -> usize {
+    fn get_knot_index(&self, u: Float) -> usize {
         for i in 0..self.knots.len()-1 { 
             if u >= self.knots[i] && u < self.knots[i+1] { 
                 return i;
@@ -93,55 +95,106 @@ impl Nurbs {
     //     None
     // }
 
-    fn get_basis(&self, knot_index: usize, u: f32) -> ([f32; 4], [f32; 4]) {
-        let mut basis = ([0., 0., 0., 1.], [0., 0., 0., 1.]);
-        let k0 = self.knots[knot_index];
-        let k1 = self.knots[knot_index + 1];
-        let k1u = k1 - u;
-        let uk0 = u - k0;
-        let k0k1 = k0 - k1;
-        let k1k0 = k1 - k0;
-        let k1u_d_k1k0 = k1u / k1k0;
-        let uk0_d_k1k0 = uk0 / k1k0;
-        if self.order > 2 { // quadratic
-            let r1 = self.knots[knot_index - 1];
-            let k2 = self.knots[knot_index + 2];
-            let w0 = self.weights[knot_index - self.order + 1];
-            let w1 = self.weights[knot_index - self.order + 2];
-            let w2 = self.weights[knot_index - self.order + 3];
-            let k0u = k0 - u;
-            let k2u = k2 - u;
-            let ur1 = u - r1;
-            let r1k2 = r1 - k2;
-            let k0k2 = k0 - k2;
-            let k1r1 = k1 - r1;
-            let k2k0 = k2 - k0;
-            let w0xk1u = w0 * k1u;
-            let w2xuk0 = w2 * uk0;
-            let p0 = w0xk1u * k1u_d_k1k0 / k1r1;
-            let p1 = w1 * (k1u_d_k1k0 * ur1/k1r1 + uk0_d_k1k0 * k2u/k2k0);
-            let p2 = w2xuk0 * uk0_d_k1k0 / k2k0;
-            let sum = p0 + p1 + p2;
-            basis.0 = [0., p0/sum, p1/sum, p2/sum];
-            let a0 = 2. * k0k1 * k0k2 * k1r1;
-            let n0 = a0 * w0xk1u * (w1 * (u-k2) - w2xuk0);
-            let n1 = a0 * w1 * (w0 * k1u * k2u - w2xuk0 * ur1);
-            let n2 = a0 * w2xuk0 * (w0 * k1u + w1 * ur1);
-            let uxu = u * u;
-            let k2xr1 = k2 * r1;
-            let ux2 = u * 2.;
-            let a1 = - w0xk1u * k0k2 * k1u + w1 * (k0 * (k1 * r1k2 + k2xr1 - r1 * ux2 + uxu) - k1*(k2xr1 - k2 * ux2 + uxu) + uxu * r1k2);
-            let d0 = a1 + w2xuk0 * uk0 * k1r1;
-            let d1 = a1 + w2 * k0u * k0u * k1r1;
-            basis.1 = [0., n0/d0/d0, n1/d0/d0, n2/d1/d1];
-        } else { // linear
-            basis.0 = [0., 0., k1u_d_k1k0, uk0_d_k1k0];
-            basis.1 = [0., 0., 1./k0k1, 1./k1k0];
+    fn get_basis(&self, knot_index: usize, u: Float) -> (Vec<Float>, Vec<Float>) {
+        let mut basis = self.get_rational_basis(knot_index, u, 1);
+        let velocity = basis.pop().unwrap_or_default();
+        let position = basis.pop().unwrap_or_default();
+        (position, velocity)
+    }
+
+    // Rational basis and its derivatives up to derivative_count for the span at knot_index.
+    // Outer index is the derivative, inner index k is for control knot_index - order + 1 + k.
+    fn get_rational_basis(&self, knot_index: usize, u: Float, derivative_count: usize) -> Vec<Vec<Float>> {
+        let weighted = self.get_weighted_basis(knot_index, u, derivative_count);
+        let sums: Vec<Float> = weighted.iter().map(|n| n.iter().sum()).collect();
+        let mut basis: Vec<Vec<Float>> = vec![];
+        for d in 0..=derivative_count {
+            let mut rational = weighted[d].clone();
+            for j in 1..=d {
+                let scale = get_binomial(d, j) * sums[j];
+                for k in 0..self.order {
+                    rational[k] -= scale * basis[d - j][k];
+                }
+            }
+            basis.push(rational.iter().map(|r| r / sums[0]).collect());
+        }
+        basis
+    }
+
+    // Basis functions and derivatives times the control weights, for evaluation in homogeneous space
+    fn get_weighted_basis(&self, knot_index: usize, u: Float, derivative_count: usize) -> Vec<Vec<Float>> {
+        let start = knot_index + 1 - self.order;
+        let weights = &self.weights[start..start + self.order];
+        self.get_unweighted_basis(knot_index, u, derivative_count).iter()
+            .map(|n| n.iter().zip(weights).map(|(n, w)| n * w).collect()).collect()
+    }
+
+    // Cox-de Boor basis functions and derivatives for the span at knot_index (The NURBS Book A2.3)
+    fn get_unweighted_basis(&self, knot_index: usize, u: Float, derivative_count: usize) -> Vec<Vec<Float>> {
+        let degree = self.order - 1;
+        let knots = &self.knots;
+        let mut ndu = vec![vec![0.; self.order]; self.order];
+        let mut left  = vec![0.; self.order];
+        let mut right = vec![0.; self.order];
+        ndu[0][0] = 1.;
+        for j in 1..=degree {
+            left[j]  = u - knots[knot_index + 1 - j];
+            right[j] = knots[knot_index + j] - u;
+            let mut saved = 0.;
+            for r in 0..j {
+                ndu[j][r] = right[r + 1] + left[j - r];
+                let temp = ndu[r][j - 1] / ndu[j][r];
+                ndu[r][j] = saved + right[r + 1] * temp;
+                saved = left[j - r] * temp;
+            }
+            ndu[j][j] = saved;
+        }
+        let mut basis = vec![vec![0.; self.order]; derivative_count + 1];
+        for j in 0..=degree {
+            basis[0][j] = ndu[j][degree];
+        }
+        let mut a = vec![vec![0.; self.order]; 2];
+        for r in 0..=degree {
+            let (mut s1, mut s2) = (0, 1);
+            a[0][0] = 1.;
+            for k in 1..=derivative_count.min(degree) {
+                let mut d = 0.;
+                let rk = r as isize - k as isize;
+                let pk = degree - k;
+                if r >= k {
+                    a[s2][0] = a[s1][0] / ndu[pk + 1][rk as usize];
+                    d = a[s2][0] * ndu[rk as usize][pk];
+                }
+                let j1 = if rk >= -1 {1} else {(-rk) as usize};
+                let j2 = if r as isize - 1 <= pk as isize {k - 1} else {degree - r};
+                for j in j1..=j2 {
+                    let rkj = (rk + j as isize) as usize;
+                    a[s2][j] = (a[s1][j] - a[s1][j - 1]) / ndu[pk + 1][rkj];
+                    d += a[s2][j] * ndu[rkj][pk];
+                }
+                if r <= pk {
+                    a[s2][k] = -a[s1][k - 1] / ndu[pk + 1][r];
+                    d += a[s2][k] * ndu[r][pk];
+                }
+                basis[k][r] = d;
+                std::mem::swap(&mut s1, &mut s2);
+            }
+        }
+        let mut factor = degree as Float;
+        for (k, derivative) in basis.iter_mut().enumerate().take(derivative_count.min(degree) + 1).skip(1) {
+            for value in derivative.iter_mut() {
+                *value *= factor;
+            }
+            factor *= (degree - k) as Float;
         }
         basis
     }
 }
 
+pub fn get_binomial(n: usize, k: usize) -> Float {
+    (0..k).fold(1., |acc, i| acc * (n - i) as Float / (i + 1) as Float)
+}
+
 
 //((k1-u)/(k1-k0)*(k1-u)/(k1-r1)*a) / k1u/k1k0*k1u/k1r1*a + 
 
@@ -400,4 +453,47 @@ impl Nurbs {
     // pub fn get_param_samples(&self, min_count: usize, max_distance: f32, controls: &Vec<Vec3>) -> Vec<f32> {
     //     let count = self.get_sample_count_with_max_distance(min_count, max_distance, controls);
     //     (0..count).map(|s| s as f32 / (count-1) as f32).collect()
-    // }
\ No newline at end of file
+    // }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_test_nurbs() -> Nurbs {
+        Nurbs {
+            order:   4,
+            knots:   vec![0., 0., 0., 0., 0.3, 0.5, 1., 1., 1., 1.],
+            weights: vec![1., 2., 0.5, 1., 1.5, 1.],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn rational_basis_is_partition_of_unity() {
+        let nurbs = get_test_nurbs();
+        for i in 0..=20 {
+            let u = i as Float / 20.;
+            let basis = nurbs.get_rational_basis(nurbs.get_knot_index(u), u, 2);
+            assert!((basis[0].iter().sum::<Float>() - 1.).abs() < 1e-5);
+            assert!(basis[1].iter().sum::<Float>().abs() < 1e-3);
+            assert!(basis[2].iter().sum::<Float>().abs() < 1e-2);
+        }
+    }
+
+    #[test]
+    fn rational_basis_derivatives_match_differences() {
+        let nurbs = get_test_nurbs();
+        let h = 1e-3;
+        for u in [0.1, 0.2, 0.4, 0.7, 0.9] {
+            let index = nurbs.get_knot_index(u);
+            let basis = nurbs.get_rational_basis(index, u, 2);
+            let before = nurbs.get_rational_basis(index, u - h, 1);
+            let after = nurbs.get_rational_basis(index, u + h, 1);
+            for d in 0..2 {
+                for k in 0..nurbs.order {
+                    let difference = (after[d][k] - before[d][k]) / (2. * h);
+                    assert!((difference - basis[d + 1][k]).abs() < 1e-2 * (1. + basis[d + 1][k].abs()));
+                }
+            }
+        }
+    }
+}
@@ -1,12 +1,20 @@
 
-use std::f32::consts::PI;
-use std::f32::EPSILON;
-use crate::{log, FacetShape};
+use crate::scalar::EPSILON;
+use crate::{log, Box3, OrientedBox3, FacetShape};
 use crate::{get_points, get_reshaped_point, get_vector_hash, query::DiscreteQuery, arrow::Arrow, scene::Polyline, Model, Shape};
-use glam::*;
+use crate::scalar::*;
 use serde::{Deserialize, Serialize};
 use super::Nurbs;
 
+mod knot;
+mod degree;
+mod length;
+mod closest;
+mod interpolate;
+mod fit;
+mod offset;
+mod corner;
+
 // ((a % b) + b) % b)  ->  a modulo b
 
 const TWO_CONTROL_POINTS: &str = "There should be two control points or more.";
@@ -16,8 +24,8 @@ const TWO_CONTROL_POINTS: &str = "There should be two control points or more.";
 pub struct Curve {
     pub controls: Vec<Model>,
     pub nurbs: Nurbs,
-    pub min:  f32,
-    pub max:  f32,
+    pub min:  Float,
+    pub max:  Float,
     pub arrows: usize,
 }
 
@@ -49,7 +57,7 @@ impl Curve {
             if let Shape::Curve(circle) = shapes[0].clone() {
                 for i in 0..self.arrows {
                     let mut curve = CurveShape::default();
-                    let ray = circle.get_arrow(i as f32 / (self.arrows - 1) as f32);
+                    let ray = circle.get_arrow(i as Float / (self.arrows - 1) as Float);
                     curve.controls.push(ray.point);
                     curve.controls.push(ray.point + ray.delta);
                     shapes.push(Shape::Curve(curve.get_valid()));
@@ -66,12 +74,47 @@ pub struct CurveRectifier {
     pub facet: FacetShape,
 }
 
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct CurveFrame {
+    pub point:     Vec3,
+    pub tangent:   Vec3,
+    pub normal:    Vec3,
+    pub binormal:  Vec3,
+    pub curvature: Float,
+    pub torsion:   Float,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct CurveProjection {
+    pub u:        Float,
+    pub point:    Vec3,
+    pub distance: Float,
+}
+
+// Deviation of fitted points from the curve at their parameters
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct CurveFit {
+    pub control_count:  usize,
+    pub max_deviation:  Float,
+    pub mean_deviation: Float,
+    pub deviations:     Vec<Float>,
+}
+
+// Spacing of curve parameters between interpolated points
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub enum Parameterization {
+    Uniform,
+    #[default]
+    ChordLength,
+    Centripetal,
+}
+
 #[derive(Clone)]
 pub struct CurveShape {
     pub controls: Vec<Vec3>,
     pub nurbs: Nurbs,
-    pub min:  f32,
-    pub max:  f32,
+    pub min:  Float,
+    pub max:  Float,
     pub rectifier: Option<Box<CurveRectifier>>,
 }
 
@@ -142,7 +185,7 @@ impl CurveShape {
         curve
     }
 
-    pub fn get_unique_knots(&self) -> Vec<f32> {
+    pub fn get_unique_knots(&self) -> Vec<Float> {
         let mut knots = vec![0.];
         for k in self.nurbs.knots.windows(2) {
             if k[0] < k[1] {
@@ -152,7 +195,22 @@ impl CurveShape {
         knots
     }
 
-    pub fn get_u_and_point_from_target(&self, u: f32, target: Vec3) -> (f32, Vec3) {
+    // Window parameters at the ends and at the knots inside the min/max window
+    fn get_span_params(&self) -> Vec<Float> {
+        let mut params = vec![0.];
+        if self.max > self.min {
+            for knot in self.get_unique_knots() {
+                let u = (knot - self.min) / (self.max - self.min);
+                if u > 0. && u < 1. {
+                    params.push(u);
+                }
+            }
+        }
+        params.push(1.);
+        params
+    }
+
+    pub fn get_u_and_point_from_target(&self, u: Float, target: Vec3) -> (Float, Vec3) {
         let ray = self.get_arrow(u);
         let length_ratio = target.length() / ray.delta.length();
         let u_dir = ray.delta.normalize().dot(target.normalize()) * length_ratio;
@@ -177,44 +235,139 @@ impl CurveShape {
         }
     }
 
-    pub fn get_polyline_vector(&self, query: &DiscreteQuery) -> Vec<f32> {
+    pub fn get_polyline_vector(&self, query: &DiscreteQuery) -> Vec<Float> {
         let curve = self.get_valid();
         let count = curve.nurbs.get_sample_count(query.count);
         (0..count).into_iter()
-            .map(|u| curve.get_point(u as f32 / (count-1) as f32).to_array()) 
+            .map(|u| curve.get_point(u as Float / (count-1) as Float).to_array()) 
             .flatten().collect()
     }
 
-    pub fn get_point(&self, u: f32) -> Vec3 {
+    pub fn get_point(&self, u: Float) -> Vec3 {
         let u = self.min * (1.-u) + self.max * u;
         let ki = self.nurbs.get_knot_index(u);      
         let basis = self.nurbs.get_basis(ki, u);
-        return (0..self.nurbs.order).map(|k| {
-            let i = 4 - self.nurbs.order + k;
-            self.controls[ki + i - 3] * basis.0[i]
-        }).sum()
+        let start = ki + 1 - self.nurbs.order;
+        (0..self.nurbs.order).map(|k| self.controls[start + k] * basis.0[k]).sum()
     }
 
-    pub fn get_arrow(&self, u: f32) -> Arrow {
+    pub fn get_arrow(&self, u: Float) -> Arrow {
         let mut ray = Arrow::new(Vec3::ZERO, Vec3::ZERO);
         let u = self.min * (1.-u) + self.max * u;    
         let ki = self.nurbs.get_knot_index(u);  
         let basis = self.nurbs.get_basis(ki, u);
+        let start = ki + 1 - self.nurbs.order;
         for k in 0..self.nurbs.order {
-            let i = 4 - self.nurbs.order + k;
-            let ci = ki - 3 + i;
-            ray.point += self.controls[ci] * basis.0[i];
-            ray.delta += self.controls[ci] * basis.1[i];
+            ray.point += self.controls[start + k] * basis.0[k];
+            ray.delta += self.controls[start + k] * basis.1[k];
         }
         ray.delta = ray.delta * (self.max - self.min);
         ray
     }
 
-    pub fn set_min(&mut self, u: f32) {
+    // Point followed by derivatives up to count with respect to u of the min/max window
+    pub fn get_derivatives(&self, u: Float, count: usize) -> Vec<Vec3> {
+        let u = self.min * (1.-u) + self.max * u;
+        let ki = self.nurbs.get_knot_index(u);
+        let basis = self.nurbs.get_rational_basis(ki, u, count);
+        let start = ki + 1 - self.nurbs.order;
+        let mut scale = 1.;
+        let mut derivatives = vec![];
+        for basis_d in basis {
+            let derivative: Vec3 = (0..self.nurbs.order).map(|k| self.controls[start + k] * basis_d[k]).sum();
+            derivatives.push(derivative * scale);
+            scale *= self.max - self.min;
+        }
+        derivatives
+    }
+
+    // Weighted point (x*w, y*w, z*w, w) and its derivatives in homogeneous space
+    pub(super) fn get_homogeneous_derivatives(&self, u: Float, count: usize) -> Vec<Vec4> {
+        let u = self.min * (1.-u) + self.max * u;
+        let ki = self.nurbs.get_knot_index(u);
+        let basis = self.nurbs.get_weighted_basis(ki, u, count);
+        let start = ki + 1 - self.nurbs.order;
+        let mut scale = 1.;
+        let mut derivatives = vec![];
+        for basis_d in basis {
+            let derivative: Vec4 = (0..self.nurbs.order).map(|k| self.controls[start + k].extend(1.) * basis_d[k]).sum();
+            derivatives.push(derivative * scale);
+            scale *= self.max - self.min;
+        }
+        derivatives
+    }
+
+    pub fn get_curvature(&self, u: Float) -> Float {
+        let d = self.get_derivatives(u, 2);
+        let speed = d[1].length();
+        if speed < EPSILON {
+            return 0.;
+        }
+        d[1].cross(d[2]).length() / (speed * speed * speed)
+    }
+
+    pub fn get_torsion(&self, u: Float) -> Float {
+        let d = self.get_derivatives(u, 3);
+        let cross = d[1].cross(d[2]);
+        if cross.length() <= EPSILON * d[1].length() * d[2].length() {
+            return 0.;
+        }
+        cross.dot(d[3]) / cross.length_squared()
+    }
+
+    // Frenet frame. The normal falls back to any perpendicular on straight spans.
+    // At a cusp or repeated control point the tangent follows the first nonzero derivative.
+    pub fn get_frame(&self, u: Float) -> CurveFrame {
+        let d = self.get_derivatives(u, self.nurbs.order.max(4) - 1);
+        let scale: Float = d[1..].iter().map(|derivative| derivative.length()).sum();
+        let Some(tangent) = d[1..].iter().find(|derivative| derivative.length() > EPSILON * scale) else {
+            return CurveFrame {
+                point: d[0],
+                ..Default::default()
+            };
+        };
+        let tangent = tangent.normalize();
+        let cross = d[1].cross(d[2]);
+        let speed = d[1].length();
+        let mut frame = CurveFrame {
+            point: d[0],
+            tangent,
+            ..Default::default()
+        };
+        if speed > EPSILON * scale && cross.length() > EPSILON * speed * d[2].length() {
+            let binormal = cross.normalize();
+            frame.binormal  = binormal;
+            frame.normal    = binormal.cross(tangent);
+            frame.curvature = cross.length() / (speed * speed * speed);
+            frame.torsion   = cross.dot(d[3]) / cross.length_squared();
+        }else{
+            frame.normal   = tangent.any_orthonormal_vector();
+            frame.binormal = tangent.cross(frame.normal);
+        }
+        frame
+    }
+
+    // Control points of the min/max window, which enclose it when weights are positive
+    pub fn get_hull(&self) -> Vec<Vec3> {
+        if self.min <= 0. && self.max >= 1. {
+            return self.controls.clone();
+        }
+        self.get_trimmed().controls
+    }
+
+    pub fn get_box3(&self) -> Box3 {
+        Box3::from_points(&self.get_hull())
+    }
+
+    pub fn get_oriented_box3(&self) -> OrientedBox3 {
+        OrientedBox3::from_points(&self.get_hull())
+    }
+
+    pub fn set_min(&mut self, u: Float) {
         self.min = self.min*(1.-u) + self.max*u;
     }
 
-    pub fn set_max(&mut self, min_basis: f32, u: f32) {
+    pub fn set_max(&mut self, min_basis: Float, u: Float) {
         self.max = min_basis*(1.-u) + self.max*u;
     }
 
@@ -638,4 +791,60 @@ impl CurveShape {
 //                 polyline.extend(self.get_vector_at_uv(u, v));
 //             }
 //         }
-//         polylines
\ No newline at end of file
+//         polylines
+#[cfg(test)]
+mod tests {
+    use crate::scalar::*;
+    use crate::{Circle, CurveShape, Shape};
+
+    #[test]
+    fn circle_frames_have_inverse_radius_curvature() {
+        let Shape::Curve(circle) = Circle {radius: 2., ..Default::default()}.get_shapes().remove(0) else {
+            panic!("circle without curve");
+        };
+        for i in 0..=20 {
+            let u = i as Float / 20.;
+            let frame = circle.get_frame(u);
+            assert!((frame.curvature - 0.5).abs() < 1e-4);
+            assert!((circle.get_curvature(u) - 0.5).abs() < 1e-4);
+            assert!(frame.torsion.abs() < 1e-4);
+            assert!(circle.get_torsion(u).abs() < 1e-4);
+            // The normal points to the center
+            assert!((frame.point + frame.normal * 2.).length() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn helix_frames_match_closed_form() {
+        let (radius, pitch) = (2., 3.);
+        let c = pitch / consts::TAU;
+        let curvature = radius / (radius * radius + c * c);
+        let torsion = c / (radius * radius + c * c);
+        let helix = CurveShape::helix(radius, pitch, 2.).unwrap();
+        for i in 1..20 {
+            let frame = helix.get_frame(i as Float / 20.);
+            assert!((frame.curvature / curvature - 1.).abs() < 1e-2);
+            assert!((frame.torsion / torsion - 1.).abs() < 1e-2);
+            // The principal normal points straight at the axis
+            assert!(frame.normal.z.abs() < 1e-2);
+            assert!(frame.normal.dot(-frame.point.truncate().extend(0.).normalize()) > 0.999);
+        }
+    }
+
+    #[test]
+    fn line_frames_fall_back_without_curvature() {
+        let line = CurveShape {
+            controls: vec![vec3(0., 0., 0.), vec3(1., 2., 3.)],
+            ..Default::default()
+        }.get_valid();
+        let frame = line.get_frame(0.5);
+        assert_eq!(frame.curvature, 0.);
+        assert_eq!(frame.torsion, 0.);
+        assert_eq!(line.get_curvature(0.5), 0.);
+        assert_eq!(line.get_torsion(0.5), 0.);
+        assert!((frame.tangent - vec3(1., 2., 3.).normalize()).length() < 1e-5);
+        assert!((frame.normal.length() - 1.).abs() < 1e-5);
+        assert!(frame.normal.dot(frame.tangent).abs() < 1e-5);
+        assert!((frame.binormal - frame.tangent.cross(frame.normal)).length() < 1e-5);
+    }
+}
@@ -0,0 +1,91 @@
+use crate::scalar::*;
+use super::{CurveShape, CurveProjection};
+
+const SPAN_SAMPLES: usize = 8;
+const NEWTON_STEPS: usize = 16;
+
+impl CurveShape {
+    // Point inversion by sampling each span and refining local minima with Newton.
+    // The parameter u is in the min/max window.
+    pub fn closest_point(&self, target: Vec3) -> CurveProjection {
+        let mut samples = vec![];
+        for span in self.get_span_params().windows(2) {
+            for i in 0..SPAN_SAMPLES {
+                let u = span[0] + (span[1] - span[0]) * i as Float / SPAN_SAMPLES as Float;
+                samples.push((u, self.get_point(u).distance(target)));
+            }
+        }
+        samples.push((1., self.get_point(1.).distance(target)));
+        let mut best = CurveProjection {
+            u: samples[0].0,
+            point: self.get_point(samples[0].0),
+            distance: samples[0].1,
+        };
+        for i in 0..samples.len() {
+            let prev = samples[i.saturating_sub(1)].1;
+            let next = samples[(i + 1).min(samples.len() - 1)].1;
+            if samples[i].1 > prev || samples[i].1 > next {
+                continue;
+            }
+            let mut projection = self.get_projection_from_u(target, samples[i].0);
+            if projection.distance > samples[i].1 {
+                projection = CurveProjection {
+                    u: samples[i].0,
+                    point: self.get_point(samples[i].0),
+                    distance: samples[i].1,
+                };
+            }
+            if projection.distance < best.distance {
+                best = projection;
+            }
+        }
+        best
+    }
+
+    // Newton iteration on (C(u) - P) . C'(u) = 0, clamped to the window
+    fn get_projection_from_u(&self, target: Vec3, u: Float) -> CurveProjection {
+        let mut u = u;
+        for _ in 0..NEWTON_STEPS {
+            let d = self.get_derivatives(u, 2);
+            let delta = d[0] - target;
+            let slope = d[2].dot(delta) + d[1].length_squared();
+            if slope.abs() <= 0. {
+                break;
+            }
+            let next = (u - d[1].dot(delta) / slope).clamp(0., 1.);
+            if (next - u).abs() < 1e-7 {
+                u = next;
+                break;
+            }
+            u = next;
+        }
+        let point = self.get_point(u);
+        CurveProjection {
+            u,
+            point,
+            distance: point.distance(target),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scalar::*;
+    use crate::{Circle, Shape};
+
+    #[test]
+    fn closest_point_on_circle_is_radial() {
+        let Shape::Curve(circle) = Circle {radius: 2., ..Default::default()}.get_shapes().remove(0) else {
+            panic!("circle without curve");
+        };
+        for i in 0..12 {
+            let direction = Vec2::from_angle(i as Float * 0.5 + 0.1).extend(0.);
+            for length in [0.5, 3.5] {
+                let projection = circle.closest_point(direction * length);
+                assert!(projection.point.distance(direction * 2.) < 1e-3);
+                assert!((projection.distance - (length - 2.).abs()).abs() < 1e-3);
+                assert!(circle.get_point(projection.u).distance(projection.point) < 1e-4);
+            }
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use crate::scalar::*;
+use crate::{Model, Revolve, Shape};
+use super::CurveShape;
+
+const CROSSING_SAMPLES: usize = 16;
+const NEWTON_STEPS: usize = 16;
+const TOLERANCE: Float = 0.0001;
+
+impl CurveShape {
+    // Round the planar corner where curve a ends and curve b starts.
+    // Returns a trimmed to the arc, the arc, and b trimmed from the arc, or None when no arc fits.
+    pub fn fillet(a: &CurveShape, b: &CurveShape, radius: Float) -> Option<(CurveShape, CurveShape, CurveShape)> {
+        let turn = get_turn(a, b)?;
+        // The arc center is where both curves offset toward the inside of the turn cross
+        let distance = -radius * turn.signum();
+        let offset_a = a.get_offset(distance, TOLERANCE)?;
+        let offset_b = b.get_offset(distance, TOLERANCE)?;
+        let (ua, ub) = get_crossing(&offset_a, &offset_b)?;
+        let center = (offset_a.get_point(ua) + offset_b.get_point(ub)) / 2.;
+        let (ua, ub) = (a.closest_point(center).u, b.closest_point(center).u);
+        if ua <= 0. || ub >= 1. {
+            return None;
+        }
+        let (start, end) = (a.get_point(ua), b.get_point(ub));
+        let arc = Revolve {
+            parts:  vec![Model::Point(start)],
+            center,
+            axis:   vec3(0., 0., turn.signum()),
+            angle:  (start - center).angle_between(end - center),
+            ..Default::default()
+        }.get_shapes().into_iter().find_map(|shape| match shape {
+            Shape::Curve(curve) => Some(curve),
+            _ => None,
+        })?;
+        Some(get_trimmed_pair(a, b, ua, ub, arc))
+    }
+
+    // Cut the planar corner where curve a ends and curve b starts with a line
+    // from the given arc length before the corner on a to the same length after it on b.
+    pub fn chamfer(a: &CurveShape, b: &CurveShape, distance: Float) -> Option<(CurveShape, CurveShape, CurveShape)> {
+        get_turn(a, b)?;
+        let length_a = a.get_length();
+        if distance <= 0. || distance >= length_a || distance >= b.get_length() {
+            return None;
+        }
+        let ua = a.get_u_at_length(length_a - distance);
+        let ub = b.get_u_at_length(distance);
+        let line = CurveShape {
+            controls: vec![a.get_point(ua), b.get_point(ub)],
+            ..Default::default()
+        }.get_valid();
+        Some(get_trimmed_pair(a, b, ua, ub, line))
+    }
+}
+
+// Signed turn from a into b, or None when they do not meet at a corner
+fn get_turn(a: &CurveShape, b: &CurveShape) -> Option<Float> {
+    if a.get_point(1.).distance(b.get_point(0.)) > TOLERANCE {
+        return None;
+    }
+    let tangent_a = a.get_derivatives(1., 1)[1].normalize_or_zero();
+    let tangent_b = b.get_derivatives(0., 1)[1].normalize_or_zero();
+    let turn = tangent_a.cross(tangent_b).z;
+    if turn.abs() <= TOLERANCE {
+        return None;
+    }
+    Some(turn)
+}
+
+fn get_trimmed_pair(a: &CurveShape, b: &CurveShape, ua: Float, ub: Float, corner: CurveShape) -> (CurveShape, CurveShape, CurveShape) {
+    let mut a = a.clone();
+    let mut b = b.clone();
+    a.set_max(a.min, ua);
+    b.set_min(ub);
+    (a, corner, b)
+}
+
+// Parameters where two planar curves cross, from the closest samples refined by Newton steps
+fn get_crossing(a: &CurveShape, b: &CurveShape) -> Option<(Float, Float)> {
+    let params: Vec<Float> = (0..=CROSSING_SAMPLES).map(|i| i as Float / CROSSING_SAMPLES as Float).collect();
+    let mut best = (0., 0., INFINITY);
+    for ua in &params {
+        for ub in &params {
+            let distance = a.get_point(*ua).distance(b.get_point(*ub));
+            if distance < best.2 {
+                best = (*ua, *ub, distance);
+            }
+        }
+    }
+    let (mut ua, mut ub) = (best.0, best.1);
+    for _ in 0..NEWTON_STEPS {
+        let da = a.get_derivatives(ua, 1);
+        let db = b.get_derivatives(ub, 1);
+        let delta = (db[0] - da[0]).truncate();
+        if delta.length() <= EPSILON {
+            break;
+        }
+        let jacobian = mat2(da[1].truncate(), -db[1].truncate());
+        if jacobian.determinant().abs() <= EPSILON {
+            return None;
+        }
+        let step = jacobian.inverse() * delta;
+        ua = (ua + step.x).clamp(0., 1.);
+        ub = (ub + step.y).clamp(0., 1.);
+    }
+    if a.get_point(ua).distance(b.get_point(ub)) > TOLERANCE {
+        return None;
+    }
+    Some((ua, ub))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scalar::*;
+    use crate::CurveShape;
+
+    fn get_line(from: Vec3, to: Vec3) -> CurveShape {
+        CurveShape {
+            controls: vec![from, to],
+            ..Default::default()
+        }.get_valid()
+    }
+
+    #[test]
+    fn fillet_arc_has_radius_and_meets_tangent() {
+        let corner = vec3(2., 1., 0.);
+        for angle in [consts::FRAC_PI_3, consts::FRAC_PI_2, 2.] {
+            for turn in [1., -1.] {
+                let a = get_line(corner - vec3(3., 0., 0.), corner);
+                let b = get_line(corner, corner + Vec2::from_angle(angle * turn).extend(0.) * 3.);
+                let (a, arc, b) = CurveShape::fillet(&a, &b, 0.5).expect("fillet");
+                let (start, end) = (arc.get_point(0.), arc.get_point(1.));
+                assert!(a.get_point(1.).distance(start) < 1e-4);
+                assert!(b.get_point(0.).distance(end) < 1e-4);
+                let center = start + vec3(0., 0.5 * turn, 0.);
+                for i in 0..=16 {
+                    assert!((arc.get_point(i as Float / 16.).distance(center) - 0.5).abs() < 1e-4);
+                }
+                let tangent = arc.get_derivatives(1., 1)[1].normalize();
+                assert!(tangent.distance(b.get_derivatives(0., 1)[1].normalize()) < 1e-3);
+            }
+        }
+    }
+
+    #[test]
+    fn chamfer_cuts_equal_lengths() {
+        let corner = vec3(0., 0., 0.);
+        let a = get_line(vec3(-2., 0., 0.), corner);
+        let b = get_line(corner, vec3(0., 3., 0.));
+        let (a, line, b) = CurveShape::chamfer(&a, &b, 0.5).expect("chamfer");
+        assert!(a.get_point(1.).distance(vec3(-0.5, 0., 0.)) < 1e-4);
+        assert!(b.get_point(0.).distance(vec3(0., 0.5, 0.)) < 1e-4);
+        assert!((line.get_length() - consts::SQRT_2 * 0.5).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,153 @@
+use crate::scalar::*;
+use crate::nurbs::get_binomial;
+use super::CurveShape;
+
+impl CurveShape {
+    // Raise the polynomial degree by count without changing the shape (The NURBS Book A5.9)
+    pub fn elevate_degree(&mut self, count: usize) -> &mut Self {
+        if count < 1 {
+            return self;
+        }
+        let p = self.nurbs.order - 1;
+        let t = count;
+        let ph = p + t;
+        let ph2 = ph / 2;
+        let knots = self.nurbs.knots.clone();
+        let m = knots.len() - 1;
+        let pw = self.get_weighted_controls();
+        let mut bezalfs = vec![vec![0.; p + 1]; ph + 1];
+        bezalfs[0][0] = 1.;
+        bezalfs[ph][p] = 1.;
+        for (i, row) in bezalfs.iter_mut().enumerate().take(ph2 + 1).skip(1) {
+            let inv = 1. / get_binomial(ph, i);
+            for (j, alf) in row.iter_mut().enumerate().take(p.min(i) + 1).skip(i.saturating_sub(t)) {
+                *alf = inv * get_binomial(p, j) * get_binomial(t, i - j);
+            }
+        }
+        for i in ph2+1..ph {
+            for j in i.saturating_sub(t)..=p.min(i) {
+                bezalfs[i][j] = bezalfs[ph - i][p - j];
+            }
+        }
+        let mut bpts = pw[..=p].to_vec();
+        let mut ebpts = vec![Vec4::ZERO; ph + 1];
+        let mut next_bpts = vec![Vec4::ZERO; p.max(1)];
+        let mut alfs = vec![0.; p.max(1)];
+        let mut qw = vec![pw[0]];
+        let mut new_knots = vec![knots[0]; ph + 1];
+        let mut r: isize = -1;
+        let mut a = p;
+        let mut b = p + 1;
+        let mut ua = knots[0];
+        while b < m {
+            let i = b;
+            while b < m && knots[b] == knots[b + 1] {
+                b += 1;
+            }
+            let mul = b - i + 1;
+            let ub = knots[b];
+            let oldr = r;
+            r = p as isize - mul as isize;
+            let lbz = if oldr > 0 {(oldr as usize + 2) / 2} else {1};
+            let rbz = if r > 0 {ph - (r as usize).div_ceil(2)} else {ph};
+            if r > 0 { // insert knot ub r times to get the bezier segment
+                let numer = ub - ua;
+                for k in (mul+1..=p).rev() {
+                    alfs[k - mul - 1] = numer / (knots[a + k] - ua);
+                }
+                for j in 1..=r as usize {
+                    let save = r as usize - j;
+                    let s = mul + j;
+                    for k in (s..=p).rev() {
+                        bpts[k] = bpts[k] * alfs[k - s] + bpts[k - 1] * (1. - alfs[k - s]);
+                    }
+                    next_bpts[save] = bpts[p];
+                }
+            }
+            for i in lbz..=ph { // degree elevate bezier segment
+                ebpts[i] = Vec4::ZERO;
+                for j in i.saturating_sub(t)..=p.min(i) {
+                    ebpts[i] += bpts[j] * bezalfs[i][j];
+                }
+            }
+            if oldr > 1 { // remove knot ua oldr times
+                let kind = new_knots.len();
+                let den = ub - ua;
+                let bet = (ub - new_knots[kind - 1]) / den;
+                for tr in 1..oldr as usize {
+                    let first = kind - 1 - tr;
+                    let last  = kind - 1 + tr;
+                    let mut i = first;
+                    let mut j = last;
+                    let mut kj = j as isize - kind as isize + 1;
+                    while j - i > tr {
+                        if i < qw.len() {
+                            let alf = (ub - new_knots[i]) / (ua - new_knots[i]);
+                            qw[i] = qw[i] * alf + qw[i - 1] * (1. - alf);
+                        }
+                        if j >= lbz {
+                            let kj = kj as usize;
+                            if j as isize - tr as isize <= kind as isize - ph as isize + oldr {
+                                let gam = (ub - new_knots[j - tr]) / den;
+                                ebpts[kj] = ebpts[kj] * gam + ebpts[kj + 1] * (1. - gam);
+                            }else{
+                                ebpts[kj] = ebpts[kj] * bet + ebpts[kj + 1] * (1. - bet);
+                            }
+                        }
+                        i += 1;
+                        j -= 1;
+                        kj -= 1;
+                    }
+                }
+            }
+            if a != p { // load the knot ua
+                for _ in 0..(ph as isize - oldr) {
+                    new_knots.push(ua);
+                }
+            }
+            qw.extend(&ebpts[lbz..=rbz]);
+            if b < m { // set up for next pass through loop
+                bpts[..r.max(0) as usize].copy_from_slice(&next_bpts[..r.max(0) as usize]);
+                for j in r.max(0) as usize..=p {
+                    bpts[j] = pw[b - p + j];
+                }
+                a = b;
+                b += 1;
+                ua = ub;
+            }else{ // end knot
+                new_knots.extend(vec![ub; ph + 1]);
+            }
+        }
+        self.nurbs.order = ph + 1;
+        self.nurbs.knots = new_knots;
+        self.set_weighted_controls(qw);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scalar::*;
+    use crate::nurbs::Nurbs;
+    use crate::CurveShape;
+
+    #[test]
+    fn elevated_curve_matches_original() {
+        let curve = CurveShape::from_nurbs_and_controls(Nurbs {
+            order:   3,
+            knots:   vec![0., 0., 0., 0.4, 0.4, 1., 1., 1.],
+            weights: vec![1., 0.7, 1., 2., 1.],
+            ..Default::default()
+        }, vec![vec3(0., 0., 0.), vec3(1., 1., 0.), vec3(2., 0., 1.), vec3(3., 2., 0.), vec3(4., 0., 0.)]);
+        for count in 1..=2 {
+            let mut elevated = curve.clone();
+            elevated.elevate_degree(count);
+            assert_eq!(elevated.nurbs.order, 3 + count);
+            assert_eq!(elevated.nurbs.knots.len(), elevated.controls.len() + elevated.nurbs.order);
+            for i in 0..=40 {
+                let u = i as Float / 40.;
+                assert!(elevated.get_point(u).distance(curve.get_point(u)) < 1e-4);
+            }
+        }
+    }
+}
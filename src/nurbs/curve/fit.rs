@@ -0,0 +1,104 @@
+use crate::scalar::*;
+use super::{CurveShape, CurveFit, Parameterization, Nurbs};
+use crate::nurbs::solve::{get_params, get_spread_knots, get_least_squares};
+
+impl CurveShape {
+    // Least-squares approximation of ordered points with fixed ends (The NURBS Book 9.4.1).
+    // Control count grows until every point is within tolerance of its parameter on the curve.
+    // Needs at least two distinct points.
+    pub fn fit(points: &[Vec3], order: usize, parameterization: Parameterization, tolerance: Float) -> Option<(CurveShape, CurveFit)> {
+        let mut points = points.to_vec();
+        points.dedup();
+        if points.len() < 2 {
+            return None;
+        }
+        let params = get_params(&points, parameterization);
+        let max_count = points.len();
+        let mut count = order.clamp(2, max_count);
+        let mut best = get_fit(&points, &params, order, parameterization, count);
+        if best.1.max_deviation <= tolerance || count >= max_count {
+            return Some(best);
+        }
+        // Double the control count until the fit passes, then bisect back down
+        let mut failed = count;
+        loop {
+            count = (count * 2).min(max_count);
+            best = get_fit(&points, &params, order, parameterization, count);
+            if best.1.max_deviation <= tolerance || count >= max_count {
+                break;
+            }
+            failed = count;
+        }
+        let mut passed = count;
+        while passed - failed > 1 {
+            let middle = (failed + passed) / 2;
+            let fit = get_fit(&points, &params, order, parameterization, middle);
+            if fit.1.max_deviation <= tolerance {
+                best = fit;
+                passed = middle;
+            }else{
+                failed = middle;
+            }
+        }
+        Some(best)
+    }
+}
+
+fn get_fit(points: &[Vec3], params: &[Float], order: usize, parameterization: Parameterization, count: usize) -> (CurveShape, CurveFit) {
+    let interpolated = if count >= points.len() {
+        CurveShape::interpolate(points, order, parameterization, None, None)
+    }else{
+        None
+    };
+    let curve = interpolated.unwrap_or_else(|| get_least_squares_curve(points, params, order.clamp(2, count), count));
+    let deviations: Vec<Float> = points.iter().zip(params)
+        .map(|(point, u)| curve.get_point(*u).distance(*point)).collect();
+    let report = CurveFit {
+        control_count: curve.controls.len(),
+        max_deviation: deviations.iter().fold(0., |a, b| a.max(*b)),
+        mean_deviation: deviations.iter().sum::<Float>() / deviations.len().max(1) as Float,
+        deviations,
+    };
+    (curve, report)
+}
+
+fn get_least_squares_curve(points: &[Vec3], params: &[Float], order: usize, count: usize) -> CurveShape {
+    let nurbs = Nurbs {
+        order,
+        knots: get_spread_knots(params, order, count),
+        weights: vec![1.; count],
+        ..Default::default()
+    };
+    let controls = get_least_squares(points, params, &nurbs);
+    CurveShape::from_nurbs_and_controls(nurbs, controls).get_valid()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scalar::*;
+    use crate::nurbs::solve::get_params;
+    use crate::{CurveShape, Parameterization};
+
+    #[test]
+    fn fit_stays_within_tolerance_with_fewer_controls() {
+        let points: Vec<Vec3> = (0..60).map(|i| {
+            let x = i as Float / 10.;
+            vec3(x, x.sin(), 0.)
+        }).collect();
+        let tolerance = 0.01;
+        let (curve, fit) = CurveShape::fit(&points, 4, Parameterization::ChordLength, tolerance).expect("fit");
+        assert!(fit.max_deviation <= tolerance);
+        assert_eq!(fit.control_count, curve.controls.len());
+        assert!(fit.control_count < points.len() / 2);
+        assert!(curve.get_point(0.).distance(points[0]) < 1e-5);
+        assert!(curve.get_point(1.).distance(points[59]) < 1e-5);
+        for ((point, u), deviation) in points.iter().zip(get_params(&points, Parameterization::ChordLength)).zip(&fit.deviations) {
+            assert!((curve.get_point(u).distance(*point) - deviation).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn fit_needs_two_distinct_points() {
+        assert!(CurveShape::fit(&[Vec3::ONE], 4, Parameterization::ChordLength, 0.01).is_none());
+    }
+}
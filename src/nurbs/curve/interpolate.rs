@@ -0,0 +1,94 @@
+use crate::scalar::*;
+use crate::nurbs::solve::{get_params, get_averaged_knots, get_basis_row, get_solution};
+use super::{CurveShape, Parameterization, Nurbs};
+
+impl CurveShape {
+    // Global interpolation through points (The NURBS Book 9.2.1) with optional end tangents (9.2.2).
+    // Tangents are directions. Their magnitude is set to the total chord length. Polylines ignore them.
+    // Needs at least two distinct points.
+    pub fn interpolate(
+        points: &[Vec3], order: usize, parameterization: Parameterization,
+        start_tangent: Option<Vec3>, end_tangent: Option<Vec3>,
+    ) -> Option<CurveShape> {
+        let mut points = points.to_vec();
+        points.dedup();
+        if points.len() < 2 {
+            return None;
+        }
+        let (start_tangent, end_tangent) = if order > 2 {(start_tangent, end_tangent)} else {(None, None)};
+        let params = get_params(&points, parameterization);
+        let length: Float = points.windows(2).map(|p| p[0].distance(p[1])).sum();
+        // End parameters are repeated for each derivative constraint so knot averaging stays valid
+        let mut knot_params = params.clone();
+        if start_tangent.is_some() {
+            knot_params.insert(0, 0.);
+        }
+        if end_tangent.is_some() {
+            knot_params.push(1.);
+        }
+        let control_count = knot_params.len();
+        let order = order.clamp(2, control_count);
+        let nurbs = Nurbs {
+            order,
+            knots: get_averaged_knots(&knot_params, order),
+            weights: vec![1.; control_count],
+            ..Default::default()
+        };
+        let mut matrix = vec![];
+        let mut values = vec![];
+        if let Some(tangent) = start_tangent {
+            matrix.push(get_basis_row(&nurbs, 0., 1));
+            values.push(tangent.normalize_or_zero() * length);
+        }
+        for (point, u) in points.iter().zip(&params) {
+            matrix.push(get_basis_row(&nurbs, *u, 0));
+            values.push(*point);
+        }
+        if let Some(tangent) = end_tangent {
+            matrix.push(get_basis_row(&nurbs, 1., 1));
+            values.push(tangent.normalize_or_zero() * length);
+        }
+        let controls = get_solution(matrix, values).unwrap_or(points);
+        Some(CurveShape::from_nurbs_and_controls(nurbs, controls).get_valid())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scalar::*;
+    use crate::nurbs::solve::get_params;
+    use crate::{CurveShape, Parameterization};
+
+    fn get_test_points() -> Vec<Vec3> {
+        vec![vec3(0., 0., 0.), vec3(1., 2., 0.), vec3(3., 3., 1.), vec3(4., 1., 0.), vec3(6., 2., -1.)]
+    }
+
+    #[test]
+    fn interpolated_curve_passes_through_points() {
+        let points = get_test_points();
+        for parameterization in [Parameterization::Uniform, Parameterization::ChordLength, Parameterization::Centripetal] {
+            let curve = CurveShape::interpolate(&points, 4, parameterization, None, None).expect("curve");
+            for (point, u) in points.iter().zip(get_params(&points, parameterization)) {
+                assert!(curve.get_point(u).distance(*point) < 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn interpolated_curve_follows_end_tangents() {
+        let points = get_test_points();
+        let (start, end) = (vec3(0., 1., 0.), vec3(1., 0., 0.));
+        let curve = CurveShape::interpolate(&points, 4, Parameterization::ChordLength, Some(start), Some(end)).expect("curve");
+        for (point, u) in points.iter().zip(get_params(&points, Parameterization::ChordLength)) {
+            assert!(curve.get_point(u).distance(*point) < 1e-4);
+        }
+        assert!(curve.get_derivatives(0., 1)[1].normalize().distance(start) < 1e-4);
+        assert!(curve.get_derivatives(1., 1)[1].normalize().distance(end) < 1e-4);
+    }
+
+    #[test]
+    fn interpolation_needs_two_distinct_points() {
+        let point = vec3(1., 2., 3.);
+        assert!(CurveShape::interpolate(&[point, point], 4, Parameterization::ChordLength, None, None).is_none());
+    }
+}
@@ -0,0 +1,487 @@
+use crate::scalar::*;
+use super::CurveShape;
+
+// Knots closer than this are the same knot, so float noise does not make near-empty spans
+const KNOT_TOLERANCE: Float = 1e-5;
+
+fn is_same_knot(a: Float, b: Float) -> bool {
+    (a - b).abs() <= KNOT_TOLERANCE
+}
+
+impl CurveShape {
+    // Boehm knot insertion in homogeneous space (The NURBS Book A5.1) for a valid curve.
+    // The knot is inserted up to count times without exceeding the polynomial degree.
+    pub fn insert_knot(&mut self, u: Float, count: usize) -> &mut Self {
+        let u = self.get_snapped_knot(u);
+        let degree = self.nurbs.order - 1;
+        let knots = self.nurbs.knots.clone();
+        if u <= knots[0] || u >= knots[knots.len()-1] {
+            return self;
+        }
+        let k = self.nurbs.get_knot_index(u);
+        let s = knots.iter().filter(|knot| is_same_knot(**knot, u)).count();
+        if s >= degree {
+            return self;
+        }
+        let r = count.min(degree - s);
+        if r < 1 {
+            return self;
+        }
+        let pw = self.get_weighted_controls();
+        let mut qw = vec![Vec4::ZERO; pw.len() + r];
+        qw[..=k-degree].copy_from_slice(&pw[..=k-degree]);
+        qw[k-s+r..].copy_from_slice(&pw[k-s..]);
+        let mut rw: Vec<Vec4> = (0..=degree-s).map(|i| pw[k-degree+i]).collect();
+        let mut l = 0;
+        for j in 1..=r {
+            l = k - degree + j;
+            for i in 0..=degree-j-s {
+                let alpha = (u - knots[l+i]) / (knots[i+k+1] - knots[l+i]);
+                rw[i] = rw[i+1] * alpha + rw[i] * (1. - alpha);
+            }
+            qw[l] = rw[0];
+            qw[k+r-j-s] = rw[degree-j-s];
+        }
+        if l + 1 < k - s {
+            qw[l+1..k-s].copy_from_slice(&rw[1..k-s-l]);
+        }
+        let mut new_knots = knots[..=k].to_vec();
+        new_knots.extend(vec![u; r]);
+        new_knots.extend(&knots[k+1..]);
+        self.nurbs.knots = new_knots;
+        self.set_weighted_controls(qw);
+        self
+    }
+
+    pub fn refine_knots(&mut self, knots: &[Float]) -> &mut Self {
+        for u in knots {
+            self.insert_knot(*u, 1);
+        }
+        self
+    }
+
+    // Remove knot u up to count times while the shape stays within tolerance (The NURBS Book A5.8).
+    // Returns the number of removals.
+    pub fn remove_knot(&mut self, u: Float, count: usize, tolerance: Float) -> usize {
+        self.remove_knot_bounded(u, count, tolerance).0
+    }
+
+    // Also returns the largest deviation bound of the removals
+    fn remove_knot_bounded(&mut self, u: Float, count: usize, tolerance: Float) -> (usize, Float) {
+        let u = self.get_snapped_knot(u);
+        let degree = self.nurbs.order - 1;
+        let order = self.nurbs.order;
+        let knots = self.nurbs.knots.clone();
+        if u <= knots[0] || u >= knots[knots.len()-1] {
+            return (0, 0.);
+        }
+        let s = knots.iter().filter(|knot| is_same_knot(**knot, u)).count();
+        if s < 1 {
+            return (0, 0.);
+        }
+        let r = knots.iter().rposition(|knot| is_same_knot(*knot, u)).unwrap_or_default();
+        let mut pw = self.get_weighted_controls();
+        let min_weight = self.nurbs.weights.iter().fold(Float::INFINITY, |a, b| a.min(*b));
+        let max_length = self.controls.iter().fold(0. as Float, |a, b| a.max(b.length()));
+        let scale = min_weight / (1. + max_length);
+        let tolerance = tolerance * scale;
+        let mut bound: Float = 0.;
+        let mut first = r - degree;
+        let mut last  = r - s;
+        let mut temp = vec![Vec4::ZERO; 2 * degree + 1];
+        let mut t = 0;
+        while t < count.min(s) {
+            let off = first - 1;
+            temp[0] = pw[off];
+            temp[last + 1 - off] = pw[last + 1];
+            let (mut i, mut j) = (first, last);
+            let (mut ii, mut jj) = (1, last - off);
+            while j > i + t {
+                let alfi = (u - knots[i]) / (knots[i + order + t] - knots[i]);
+                let alfj = (u - knots[j - t]) / (knots[j + order] - knots[j - t]);
+                temp[ii] = (pw[i] - temp[ii - 1] * (1. - alfi)) / alfi;
+                temp[jj] = (pw[j] - temp[jj + 1] * alfj) / (1. - alfj);
+                i += 1;
+                ii += 1;
+                j -= 1;
+                jj -= 1;
+            }
+            let distance = if j < i + t {
+                temp[ii - 1].distance(temp[jj + 1])
+            }else{
+                let alfi = (u - knots[i]) / (knots[i + order + t] - knots[i]);
+                pw[i].distance(temp[ii + t + 1] * alfi + temp[ii - 1] * (1. - alfi))
+            };
+            if distance > tolerance {
+                break;
+            }
+            bound = bound.max(distance / scale);
+            let (mut i, mut j) = (first, last);
+            while j > i + t {
+                pw[i] = temp[i - off];
+                pw[j] = temp[j - off];
+                i += 1;
+                j -= 1;
+            }
+            first -= 1;
+            last  += 1;
+            t += 1;
+        }
+        if t < 1 {
+            return (0, 0.);
+        }
+        let mut new_knots = knots[..=r - t].to_vec();
+        new_knots.extend(&knots[r + 1..]);
+        let fout = (2 * r - s - degree) / 2;
+        let mut i = fout;
+        let mut j = fout;
+        for k in 1..t {
+            if k % 2 == 1 {i += 1;} else {j -= 1;}
+        }
+        let mut controls = pw[..j].to_vec();
+        controls.extend(&pw[i + 1..]);
+        self.nurbs.knots = new_knots;
+        self.set_weighted_controls(controls);
+        (t, bound)
+    }
+
+    // Remove interior knots in one pass while the summed removal bounds (The NURBS Book A5.8)
+    // of the spans each removal changes stay within tolerance of the original shape
+    pub fn simplify(&mut self, tolerance: Float) -> &mut Self {
+        CurveShape::simplify_together(std::slice::from_mut(self), &[tolerance]);
+        self
+    }
+
+    // Simplify curves that are evaluated side by side. A knot goes only when every curve has it
+    // and every curve stays within its own tolerance, so curves sharing a knot vector keep sharing it.
+    pub fn simplify_together(curves: &mut [CurveShape], tolerances: &[Float]) {
+        let Some(first) = curves.first() else {
+            return;
+        };
+        let mut errors: Vec<Vec<(Float, Float, Float)>> = vec![vec![]; curves.len()];
+        for (u, multiplicity) in first.get_knot_multiplicities() {
+            for _ in 0..multiplicity {
+                let mut removed = curves.to_vec();
+                let mut spans = vec![];
+                for ((curve, tolerance), errors) in removed.iter_mut().zip(tolerances).zip(&errors) {
+                    let degree = curve.nurbs.order - 1;
+                    let knots = &curve.nurbs.knots;
+                    if u <= knots[0] || u >= knots[knots.len()-1] {
+                        break;
+                    }
+                    let s = knots.iter().filter(|knot| is_same_knot(**knot, u)).count();
+                    let Some(r) = knots.iter().rposition(|knot| is_same_knot(*knot, u)) else {
+                        break;
+                    };
+                    let (min, max) = (knots[r - degree], knots[r - s + degree + 1]);
+                    let error = errors.iter()
+                        .filter(|(a, b, _)| *a < max && *b > min)
+                        .fold(0., |error: Float, (_, _, e)| error.max(*e));
+                    let (count, bound) = curve.remove_knot_bounded(u, 1, tolerance - error);
+                    if count < 1 {
+                        break;
+                    }
+                    spans.push((min, max, error + bound));
+                }
+                if spans.len() == curves.len() {
+                    curves.clone_from_slice(&removed);
+                    for (errors, span) in errors.iter_mut().zip(spans) {
+                        errors.push(span);
+                    }
+                } else {
+                    break;
+                }
+            }
+        }
+    }
+
+    // Split the min/max window at u into two independent curves with their own knot vectors.
+    // None when u is at or beyond an end of the window, where one piece would be empty.
+    pub fn split_at(&self, u: Float) -> Option<(CurveShape, CurveShape)> {
+        let knot = self.min * (1.-u) + self.max * u;
+        if knot <= self.min + KNOT_TOLERANCE || knot >= self.max - KNOT_TOLERANCE {
+            return None;
+        }
+        Some((self.get_sub_curve(self.min, knot), self.get_sub_curve(knot, self.max)))
+    }
+
+    // Exact curve of the min/max window with min 0 and max 1
+    pub fn get_trimmed(&self) -> CurveShape {
+        self.get_sub_curve(self.min, self.max)
+    }
+
+    // Exact window curve with polylines split into their lines so corners between them can be handled
+    pub fn get_smooth_pieces(&self) -> Vec<CurveShape> {
+        let curve = self.get_trimmed();
+        if curve.nurbs.order > 2 || curve.controls.len() < 3 {
+            return vec![curve];
+        }
+        curve.controls.windows(2).map(|pair| CurveShape {
+            controls: pair.to_vec(),
+            ..Default::default()
+        }.get_valid()).collect()
+    }
+
+    fn get_sub_curve(&self, min: Float, max: Float) -> CurveShape {
+        let mut curve = self.get_valid();
+        curve.min = 0.;
+        curve.max = 1.;
+        if max < curve.nurbs.knots[curve.nurbs.knots.len()-1] - KNOT_TOLERANCE {
+            curve = curve.get_split_at_knot(max).0;
+        }
+        if min > curve.nurbs.knots[0] + KNOT_TOLERANCE {
+            curve = curve.get_split_at_knot(min).1;
+        }
+        curve.nurbs.normalize_knots();
+        curve
+    }
+
+    fn get_split_at_knot(&self, u: Float) -> (CurveShape, CurveShape) {
+        let u = self.get_snapped_knot(u);
+        let mut curve = self.clone();
+        curve.insert_knot(u, curve.nurbs.order);
+        let degree = curve.nurbs.order - 1;
+        let knots = &curve.nurbs.knots;
+        let a = knots.iter().position(|knot| is_same_knot(*knot, u)).unwrap_or(knots.len() - 1);
+        let s = knots.iter().filter(|knot| is_same_knot(**knot, u)).count();
+        let mut left = curve.clone();
+        left.nurbs.knots = knots[..a+degree].to_vec();
+        left.nurbs.knots.push(u);
+        left.controls = curve.controls[..a].to_vec();
+        left.nurbs.weights = curve.nurbs.weights[..a].to_vec();
+        let mut right = curve.clone();
+        let b = a + s - degree - 1;
+        right.nurbs.knots = vec![u];
+        right.nurbs.knots.extend(&knots[b+1..]);
+        right.controls = curve.controls[b..].to_vec();
+        right.nurbs.weights = curve.nurbs.weights[b..].to_vec();
+        (left, right)
+    }
+
+    // Curves of one order and knot vector with the same shapes, ready to form a tensor product net
+    pub fn get_compatible(curves: &[CurveShape]) -> Vec<CurveShape> {
+        let mut curves: Vec<CurveShape> = curves.iter().map(|c| c.get_trimmed()).collect();
+        let order = curves.iter().map(|c| c.nurbs.order).max().unwrap_or(2);
+        for curve in &mut curves {
+            curve.elevate_degree(order - curve.nurbs.order);
+        }
+        let mut knots: Vec<(Float, usize)> = vec![];
+        for curve in &curves {
+            for (u, count) in curve.get_knot_multiplicities() {
+                match knots.iter_mut().find(|(k, _)| is_same_knot(*k, u)) {
+                    Some(knot) => knot.1 = knot.1.max(count),
+                    None => knots.push((u, count)),
+                }
+            }
+        }
+        for curve in &mut curves {
+            let multiplicities = curve.get_knot_multiplicities();
+            for (u, count) in &knots {
+                let existing = multiplicities.iter().find(|(k, _)| is_same_knot(*k, *u)).map_or(0, |k| k.1);
+                if *count > existing {
+                    curve.insert_knot(*u, count - existing);
+                }
+            }
+        }
+        curves
+    }
+
+    // Chain of curves meeting end to start as one curve of the highest order.
+    // Knot spans follow the piece lengths and joints keep a full multiplicity, so every piece keeps its shape.
+    pub fn get_joined(curves: &[CurveShape]) -> CurveShape {
+        let mut curves: Vec<CurveShape> = curves.iter().map(|c| c.get_trimmed()).collect();
+        let order = curves.iter().map(|c| c.nurbs.order).max().unwrap_or(2);
+        for curve in &mut curves {
+            curve.elevate_degree(order - curve.nurbs.order);
+        }
+        let Some((first, rest)) = curves.split_first() else {
+            return CurveShape::default();
+        };
+        let mut joined = first.clone();
+        let length = first.get_length().max(EPSILON);
+        joined.nurbs.knots.iter_mut().for_each(|knot| *knot *= length);
+        for curve in rest {
+            let end = joined.nurbs.knots.pop().unwrap_or(0.);
+            let length = curve.get_length().max(EPSILON);
+            joined.nurbs.knots.extend(curve.nurbs.knots[order..].iter().map(|knot| end + knot * length));
+            // Homogeneous scaling leaves the piece unchanged and lets it share the joint weight
+            let mut controls = joined.get_weighted_controls();
+            let next = curve.get_weighted_controls();
+            let scale = controls[controls.len() - 1].w / next[0].w;
+            controls.extend(next[1..].iter().map(|p| *p * scale));
+            joined.set_weighted_controls(controls);
+        }
+        joined.nurbs.normalize_knots();
+        joined
+    }
+
+    fn get_knot_multiplicities(&self) -> Vec<(Float, usize)> {
+        let mut knots: Vec<(Float, usize)> = vec![];
+        for u in &self.nurbs.knots {
+            match knots.last_mut() {
+                Some(knot) if is_same_knot(knot.0, *u) => knot.1 += 1,
+                _ => knots.push((*u, 1)),
+            }
+        }
+        knots
+    }
+
+    // Existing knot within tolerance of u, or u itself
+    fn get_snapped_knot(&self, u: Float) -> Float {
+        self.nurbs.knots.iter().copied().find(|knot| is_same_knot(*knot, u)).unwrap_or(u)
+    }
+
+    pub(super) fn get_weighted_controls(&self) -> Vec<Vec4> {
+        self.controls.iter().zip(&self.nurbs.weights).map(|(p, w)| (*p * *w).extend(*w)).collect()
+    }
+
+    pub(super) fn set_weighted_controls(&mut self, controls: Vec<Vec4>) {
+        self.nurbs.weights = controls.iter().map(|p| p.w).collect();
+        self.controls = controls.iter().map(|p| p.truncate() / p.w).collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scalar::*;
+    use crate::nurbs::Nurbs;
+    use crate::CurveShape;
+
+    fn get_test_curve() -> CurveShape {
+        CurveShape::from_nurbs_and_controls(Nurbs {
+            order:   4,
+            knots:   vec![0., 0., 0., 0., 0.3, 0.6, 1., 1., 1., 1.],
+            weights: vec![1., 0.5, 2., 1., 1.5, 1.],
+            ..Default::default()
+        }, vec![
+            vec3(0., 0., 0.), vec3(1., 2., 0.), vec3(2., -1., 1.),
+            vec3(3., 1., 0.), vec3(4., 3., -1.), vec3(5., 0., 0.),
+        ])
+    }
+
+    fn assert_same_shape(a: &CurveShape, b: &CurveShape) {
+        for i in 0..=40 {
+            let u = i as Float / 40.;
+            assert!(a.get_point(u).distance(b.get_point(u)) < 1e-4);
+        }
+    }
+
+    #[test]
+    fn inserted_knots_keep_shape_and_can_be_removed() {
+        let curve = get_test_curve();
+        let mut refined = curve.clone();
+        refined.insert_knot(0.45, 2).insert_knot(0.3, 1);
+        assert_eq!(refined.controls.len(), curve.controls.len() + 3);
+        assert_eq!(refined.nurbs.knots.len(), curve.nurbs.knots.len() + 3);
+        assert_same_shape(&curve, &refined);
+        assert_eq!(refined.remove_knot(0.45, 2, 1e-4), 2);
+        assert_eq!(refined.remove_knot(0.3, 1, 1e-4), 1);
+        assert_eq!(refined.controls.len(), curve.controls.len());
+        assert_same_shape(&curve, &refined);
+    }
+
+    #[test]
+    fn needed_knots_are_not_removed() {
+        let mut curve = get_test_curve();
+        assert_eq!(curve.remove_knot(0.3, 1, 1e-4), 0);
+        assert_eq!(curve.controls.len(), 6);
+    }
+
+    #[test]
+    fn split_halves_follow_the_curve() {
+        let curve = get_test_curve();
+        let (left, right) = curve.split_at(0.4).unwrap();
+        for i in 0..=20 {
+            let v = i as Float / 20.;
+            assert!(left.get_point(v).distance(curve.get_point(0.4 * v)) < 1e-4);
+            assert!(right.get_point(v).distance(curve.get_point(0.4 + 0.6 * v)) < 1e-4);
+        }
+    }
+
+    fn get_max_distance(a: &CurveShape, b: &CurveShape) -> Float {
+        (0..=200).map(|i| {
+            let u = i as Float / 200.;
+            a.get_point(u).distance(b.get_point(u))
+        }).fold(0., Float::max)
+    }
+
+    #[test]
+    fn simplify_removes_only_redundant_knots() {
+        let curve = get_test_curve();
+        let mut refined = curve.clone();
+        refined.refine_knots(&[0.15, 0.45, 0.45, 0.8]);
+        refined.simplify(1e-4);
+        assert_eq!(refined.nurbs.knots, curve.nurbs.knots);
+        assert_same_shape(&curve, &refined);
+    }
+
+    #[test]
+    fn simplify_stays_within_tolerance() {
+        let curve = get_test_curve();
+        for tolerance in [0.01, 0.1, 0.5, 2.] {
+            let mut simple = curve.clone();
+            simple.simplify(tolerance);
+            assert!(get_max_distance(&curve, &simple) <= tolerance);
+        }
+        let mut simple = curve.clone();
+        simple.simplify(1e-4);
+        assert_eq!(simple.controls.len(), curve.controls.len());
+    }
+
+    #[test]
+    fn simplify_together_keeps_shared_knots() {
+        let mut refined = get_test_curve();
+        refined.refine_knots(&[0.15, 0.45, 0.8]);
+        let mut flat = refined.clone();
+        flat.controls.iter_mut().for_each(|p| p.z = 0.);
+        let mut curves = [refined.clone(), flat.clone()];
+        CurveShape::simplify_together(&mut curves, &[0.05, 0.05]);
+        assert_eq!(curves[0].nurbs.knots, curves[1].nurbs.knots);
+        assert!(get_max_distance(&refined, &curves[0]) <= 0.05);
+        assert!(get_max_distance(&flat, &curves[1]) <= 0.05);
+    }
+
+    #[test]
+    fn split_at_the_ends_is_rejected() {
+        let curve = get_test_curve();
+        for u in [-0.5, 0., 1e-7, 1. - 1e-7, 1., 1.5] {
+            assert!(curve.split_at(u).is_none());
+        }
+    }
+
+    #[test]
+    fn nearly_equal_knots_are_the_same_knot() {
+        let curve = get_test_curve();
+        let mut refined = curve.clone();
+        refined.insert_knot(0.3 + 1e-7, 1);
+        assert_eq!(refined.nurbs.knots.iter().filter(|knot| **knot == 0.3).count(), 2);
+        assert_eq!(refined.remove_knot(0.3 - 1e-7, 1, 1e-4), 1);
+        assert_eq!(refined.nurbs.knots, curve.nurbs.knots);
+        let (left, right) = curve.split_at(0.3 + 1e-7).unwrap();
+        assert!(left.controls.iter().chain(&right.controls).all(|p| p.is_finite()));
+        assert!(left.get_point(1.).distance(curve.get_point(0.3)) < 1e-4);
+        assert!(right.get_point(0.).distance(curve.get_point(0.3)) < 1e-4);
+    }
+
+    #[test]
+    fn compatible_curves_keep_their_shapes() {
+        let line = CurveShape {
+            controls: vec![vec3(0., 0., 1.), vec3(5., 0., 1.)],
+            ..Default::default()
+        }.get_valid();
+        let quadratic = CurveShape::from_nurbs_and_controls(Nurbs {
+            order:   3,
+            knots:   vec![0., 0., 0., 0.5, 1., 1., 1.],
+            weights: vec![1., 1., 1., 1.],
+            ..Default::default()
+        }, vec![vec3(0., 0., 2.), vec3(1., 3., 2.), vec3(4., 3., 2.), vec3(5., 0., 2.)]);
+        let curves = vec![get_test_curve(), line, quadratic];
+        let compatible = CurveShape::get_compatible(&curves);
+        for (curve, input) in compatible.iter().zip(&curves) {
+            assert_eq!(curve.nurbs.order, 4);
+            assert_eq!(curve.nurbs.knots, compatible[0].nurbs.knots);
+            assert_eq!(curve.controls.len(), compatible[0].controls.len());
+            assert_same_shape(input, curve);
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use crate::scalar::*;
+use super::CurveShape;
+
+// Gauss-Legendre nodes and weights on [-1, 1]
+const GAUSS_NODES: [Float; 5] = [-0.906_179_85, -0.538_469_3, 0., 0.538_469_3, 0.906_179_85];
+const GAUSS_WEIGHTS: [Float; 5] = [0.236_926_89, 0.478_628_67, 0.568_888_9, 0.478_628_67, 0.236_926_89];
+const SPAN_DIVISIONS: usize = 4;
+const NEWTON_STEPS: usize = 8;
+
+impl CurveShape {
+    // Arc length of the min/max window
+    pub fn get_length(&self) -> Float {
+        self.get_length_table().last().map(|(_, length)| *length).unwrap_or_default()
+    }
+
+    // Window parameter at the given arc length from the start of the window
+    pub fn get_u_at_length(&self, length: Float) -> Float {
+        self.get_u_at_length_in_table(&self.get_length_table(), length)
+    }
+
+    // Points spaced at equal arc length, including both ends of the window
+    pub fn get_evenly_spaced_points(&self, count: usize) -> Vec<Vec3> {
+        self.get_evenly_spaced_params(count).into_iter().map(|u| self.get_point(u)).collect()
+    }
+
+    pub fn get_evenly_spaced_params(&self, count: usize) -> Vec<Float> {
+        if count < 2 {
+            return vec![0.; count];
+        }
+        let table = self.get_length_table();
+        let length = table.last().map(|(_, length)| *length).unwrap_or_default();
+        (0..count).map(|i| {
+            self.get_u_at_length_in_table(&table, length * i as Float / (count - 1) as Float)
+        }).collect()
+    }
+
+    fn get_u_at_length_in_table(&self, table: &[(Float, Float)], length: Float) -> Float {
+        let total = table.last().map(|(_, length)| *length).unwrap_or_default();
+        if length <= 0. || total <= 0. {
+            return 0.;
+        }
+        if length >= total {
+            return 1.;
+        }
+        let i = table.partition_point(|(_, l)| *l <= length).clamp(1, table.len() - 1);
+        let (u0, l0) = table[i - 1];
+        let (u1, l1) = table[i];
+        let mut u = u0 + (u1 - u0) * (length - l0) / (l1 - l0);
+        for _ in 0..NEWTON_STEPS {
+            let error = l0 + self.get_length_between(u0, u) - length;
+            let speed = self.get_derivatives(u, 1)[1].length();
+            if speed <= 0. {
+                break;
+            }
+            let next = (u - error / speed).clamp(u0, u1);
+            if (next - u).abs() < 1e-7 {
+                u = next;
+                break;
+            }
+            u = next;
+        }
+        u
+    }
+
+    // Window parameters at the knots paired with the cumulative arc length
+    fn get_length_table(&self) -> Vec<(Float, Float)> {
+        let params = self.get_span_params();
+        let mut table = vec![(0., 0.)];
+        for span in params.windows(2) {
+            for i in 0..SPAN_DIVISIONS {
+                let u0 = span[0] + (span[1] - span[0]) * i as Float / SPAN_DIVISIONS as Float;
+                let u1 = span[0] + (span[1] - span[0]) * (i + 1) as Float / SPAN_DIVISIONS as Float;
+                let length = table[table.len() - 1].1 + self.get_length_between(u0, u1);
+                table.push((u1, length));
+            }
+        }
+        table
+    }
+
+    fn get_length_between(&self, u0: Float, u1: Float) -> Float {
+        let half = (u1 - u0) / 2.;
+        let mid = (u0 + u1) / 2.;
+        GAUSS_NODES.iter().zip(GAUSS_WEIGHTS).map(|(node, weight)| {
+            weight * self.get_derivatives(mid + half * node, 1)[1].length()
+        }).sum::<Float>() * half
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scalar::*;
+    use crate::nurbs::Nurbs;
+    use crate::{Circle, CurveShape, Shape};
+
+    #[test]
+    fn circle_length_is_circumference() {
+        let Shape::Curve(circle) = Circle {radius: 3., ..Default::default()}.get_shapes().remove(0) else {
+            panic!("circle without curve");
+        };
+        assert!((circle.get_length() - consts::TAU * 3.).abs() < 1e-3);
+        let (half, _) = circle.split_at(0.5).unwrap();
+        assert!((half.get_length() - consts::PI * 3.).abs() < 1e-3);
+    }
+
+    #[test]
+    fn evenly_spaced_points_split_the_length() {
+        let curve = CurveShape {
+            controls: vec![vec3(0., 0., 0.), vec3(1., 3., 0.), vec3(4., 3., 0.), vec3(5., 0., 0.)],
+            nurbs: Nurbs {order: 4, ..Default::default()},
+            ..Default::default()
+        }.get_valid();
+        let length = curve.get_length();
+        let params = curve.get_evenly_spaced_params(9);
+        assert_eq!(params.len(), 9);
+        for (i, u) in params.iter().enumerate().take(8).skip(1) {
+            let (start, _) = curve.split_at(*u).unwrap();
+            assert!((start.get_length() - length * i as Float / 8.).abs() < 1e-3);
+        }
+    }
+}
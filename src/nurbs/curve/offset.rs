@@ -0,0 +1,115 @@
+use crate::scalar::*;
+use super::{CurveShape, Parameterization};
+
+const ARC_SAMPLES: usize = 8;
+const FIT_SAMPLES: usize = 32;
+
+impl CurveShape {
+    // Planar offset in XY toward the right of the curve direction, which is outward for counterclockwise loops.
+    // Lines and circular arcs stay exact. Other curves are fitted to offset samples within tolerance.
+    // None when an arc collapses to its center.
+    pub fn get_offset(&self, distance: Float, tolerance: Float) -> Option<CurveShape> {
+        let curve = self.get_trimmed();
+        if curve.nurbs.order == 2 && curve.controls.len() == 2 {
+            let normal = get_right_normal(curve.controls[1] - curve.controls[0]);
+            return Some(curve.get_reshape(Mat4::from_translation(normal * distance)));
+        }
+        if let Some(center) = curve.get_arc_center(tolerance) {
+            let start = curve.get_point(0.);
+            let radius = start.distance(center);
+            let normal = get_right_normal(curve.get_derivatives(0., 1)[1]);
+            let offset = if normal.dot(start - center) > 0. {radius + distance} else {radius - distance};
+            if offset <= tolerance {
+                return None;
+            }
+            let mat4 = Mat4::from_translation(center)
+                * Mat4::from_scale(vec3(offset / radius, offset / radius, 1.))
+                * Mat4::from_translation(-center);
+            return Some(curve.get_reshape(mat4));
+        }
+        let count = FIT_SAMPLES * curve.nurbs.knots.len();
+        let points: Vec<Vec3> = (0..=count).map(|i| {
+            let d = curve.get_derivatives(i as Float / count as Float, 1);
+            d[0] + get_right_normal(d[1]) * distance
+        }).collect();
+        CurveShape::fit(&points, curve.nurbs.order.max(4), Parameterization::ChordLength, tolerance).map(|(curve, _)| curve)
+    }
+
+    // Center of a rational quadratic curve that stays on one circle.
+    // Samples skip the end so closed circles still give three distinct points.
+    fn get_arc_center(&self, tolerance: Float) -> Option<Vec3> {
+        if self.nurbs.order != 3 || self.nurbs.weights.iter().all(|w| *w == 1.) {
+            return None;
+        }
+        let (a, b, c) = (self.get_point(0.), self.get_point(1. / 3.), self.get_point(2. / 3.));
+        let (ab, ac) = (b - a, c - a);
+        let normal = ab.cross(ac);
+        if normal.length_squared() <= EPSILON {
+            return None;
+        }
+        let center = a + (normal.cross(ab) * ac.length_squared() + ac.cross(normal) * ab.length_squared())
+            / (2. * normal.length_squared());
+        let radius = a.distance(center);
+        for i in 1..ARC_SAMPLES {
+            if (self.get_point(i as Float / ARC_SAMPLES as Float).distance(center) - radius).abs() > tolerance {
+                return None;
+            }
+        }
+        Some(center)
+    }
+}
+
+// Unit normal to the right of a direction in the XY plane
+fn get_right_normal(direction: Vec3) -> Vec3 {
+    direction.cross(Vec3::Z).normalize_or_zero()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scalar::*;
+    use crate::nurbs::Nurbs;
+    use crate::{Circle, CurveShape, Shape};
+
+    #[test]
+    fn full_circle_offsets_stay_on_circles() {
+        let center = vec2(1., -2.);
+        let Shape::Curve(circle) = Circle {center, radius: 2., ..Default::default()}.get_shapes().remove(0) else {
+            panic!("circle without curve");
+        };
+        for distance in [0.5, -0.5] {
+            let offset = circle.get_offset(distance, 0.001).expect("offset circle");
+            assert_eq!(offset.nurbs.order, 3);
+            for i in 0..=64 {
+                let point = offset.get_point(i as Float / 64.);
+                assert!((point.truncate().distance(center) - (2. + distance)).abs() < 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn line_offsets_move_to_the_right() {
+        let line = CurveShape {
+            controls: vec![vec3(0., 0., 0.), vec3(2., 0., 0.)],
+            ..Default::default()
+        }.get_valid();
+        let offset = line.get_offset(0.5, 0.001).expect("offset line");
+        assert!(offset.get_point(0.).distance(vec3(0., -0.5, 0.)) < 1e-6);
+        assert!(offset.get_point(1.).distance(vec3(2., -0.5, 0.)) < 1e-6);
+    }
+
+    #[test]
+    fn fitted_offsets_stay_within_tolerance() {
+        let curve = CurveShape::from_nurbs_and_controls(Nurbs {
+            order: 4,
+            ..Default::default()
+        }, vec![vec3(0., 0., 0.), vec3(1., 2., 0.), vec3(3., -1., 0.), vec3(4., 1., 0.)]).get_valid();
+        let tolerance = 0.001;
+        for distance in [0.2, -0.2] {
+            let offset = curve.get_offset(distance, tolerance).expect("offset curve");
+            for i in 0..=64 {
+                let point = offset.get_point(i as Float / 64.);
+                assert!((curve.closest_point(point).distance - distance.abs()).abs() < 2. * tolerance);
+            }
+        }
+    }
+}
@@ -1,15 +1,18 @@
-use std::f32::{EPSILON, INFINITY};
-use crate::nurbs::Nurbs;
+use crate::scalar::{EPSILON, INFINITY};
+use crate::nurbs::{Nurbs, get_binomial};
 use crate::query::DiscreteQuery;
 use crate::scene::Mesh;
-use crate::{get_curves, get_line_intersection2, get_vector_hash, CurveShape, Model, Rectangle, Shape};
+use crate::{get_curves, get_line_intersection2, get_vector_hash, Box3, OrientedBox3, CurveShape, Model, Rectangle, Shape};
 //use euclid::{point3, Box3D, Point3D};
-use glam::*;
+use crate::scalar::*;
 use serde::{Deserialize, Serialize};
 use lyon::tessellation::*;
 use lyon::geom::{Box2D, Point};
 use lyon::path::Winding;
 
+mod closest;
+mod loft;
+
 // ((a % b) + b) % b)  ->  a modulo b
 
 const TWO_CONTROLS: &str = "There should be two control curves or more.";
@@ -24,14 +27,34 @@ pub struct Facet {
 
 impl Facet {
     pub fn get_shapes(&self) -> Vec<Shape> {
+        // User-built controls can mix orders and knots, so they are made compatible for a tensor product surface
         vec![Shape::Facet(FacetShape{
-            controls: get_curves(&self.controls),
+            controls: CurveShape::get_compatible(&get_curves(&self.controls)),
             boundaries: get_curves(&self.boundaries.clone()),
             nurbs: self.nurbs.clone(),
         }.get_valid())]
     }
 }
 
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct FacetProjection {
+    pub uv:       Vec2,
+    pub point:    Vec3,
+    pub normal:   Vec3,
+    pub distance: Float,
+}
+
+#[derive(Clone, Default)]
+pub struct FacetCurvature {
+    pub normal:        Vec3,
+    pub min:           Float,
+    pub max:           Float,
+    pub min_direction: Vec3,
+    pub max_direction: Vec3,
+    pub gaussian:      Float,
+    pub mean:          Float,
+}
+
 #[derive(Clone)]
 pub struct FacetShape {
     pub nurbs:      Nurbs,
@@ -110,16 +133,18 @@ impl FacetShape {
         facet
     }
 
-    // pub fn get_box3(&self) -> Box3D<f32, f32> {
-    //     let mut points = vec![];
-    //     for curve in &self.controls {
-    //         for control in &curve.controls {
-    //             points.push(Point3D::from(control.to_array()));
-    //         }
-    //     }
-    //     let box3 = Box3D::from_points(points);
-    //     box3.inflate(0.01, 0.01, 0.01)
-    // }
+    // Control points of every control curve. Trim boundaries are not used, so boxes stay conservative.
+    pub fn get_hull(&self) -> Vec<Vec3> {
+        self.controls.iter().flat_map(|curve| curve.get_hull()).collect()
+    }
+
+    pub fn get_box3(&self) -> Box3 {
+        Box3::from_points(&self.get_hull())
+    }
+
+    pub fn get_oriented_box3(&self) -> OrientedBox3 {
+        OrientedBox3::from_points(&self.get_hull())
+    }
 
     // pub fn get_normalized_knots(&self) -> Vec<Vec2> {
     //     let mut knots = vec![];
@@ -208,12 +233,12 @@ impl FacetShape {
         //     builder.add_rectangle(&Box2D{min:Point::new(0., 0.), max:Point::new(1., 1.)}, Winding::Positive);
         // }
         for ui in 0..u_count {
-            let u = ui as f32 / (u_count-1) as f32;
-            builder.add_rectangle(&Box2D{min:Point::new(u, 0.), max:Point::new(u, 1.)}, Winding::Positive);
+            let u = ui as Float / (u_count-1) as Float;
+            builder.add_rectangle(&Box2D{min:Point::new(to_f32(u), 0.), max:Point::new(to_f32(u), 1.)}, Winding::Positive);
         }
         for vi in 0..v_count {
-            let v = vi as f32 / (v_count-1) as f32;
-            builder.add_rectangle(&Box2D{min:Point::new(0., v), max:Point::new(1., v)}, Winding::Positive);
+            let v = vi as Float / (v_count-1) as Float;
+            builder.add_rectangle(&Box2D{min:Point::new(0., to_f32(v)), max:Point::new(1., to_f32(v))}, Winding::Positive);
         }
         let mut loop_open = false;
         let mut bndry_i = 0;
@@ -224,7 +249,7 @@ impl FacetShape {
             for p in bndry.get_polyline_vector(query).chunks(3) {
                 //let mut y = p[1];
                 //////if facet.reversed {y = 1.-y;}
-                let point = lyon::geom::Point::new(p[0], p[1]); // y
+                let point = lyon::geom::Point::new(to_f32(p[0]), to_f32(p[1])); // y
                 if loop_open {
                     builder.line_to(point);
                 }else{
@@ -255,7 +280,7 @@ impl FacetShape {
         tessellator.tessellate_path(&path, &options, &mut buffer_builder).expect("Tessellation failed");
         let mut vector = vec![];
         for [u, v] in geometry.vertices.into_iter(){
-            vector.extend(facet.get_point(vec2(u, v)).to_array());
+            vector.extend(facet.get_point(vec2(u as Float, v as Float)).to_array());
         }
         let mut trivec = geometry.indices;
         for k in 0..trivec.len()/3 {
@@ -285,21 +310,110 @@ impl FacetShape {
         bndry_i
     }
 
+    // Rational tensor product evaluated in homogeneous space
     pub fn get_point(&self, uv: Vec2) -> Vec3 {
-        let mut point = Vec3::ZERO;
-        // let knot_index = self.nurbs.get_knot_index(uv.y);
-        // if let Some(ki) = knot_index {
-            let ki = self.nurbs.get_knot_index(uv.y);
-            let basis = self.nurbs.get_basis(ki, uv.y); 
-            for k in 0..self.nurbs.order {
-                let i = 4 - self.nurbs.order + k;
-                let ci = ki - 3 + i;
-                point += self.controls[ci].get_point(uv.x) * basis.0[i];
+        let ki = self.nurbs.get_knot_index(uv.y);
+        let basis = self.nurbs.get_weighted_basis(ki, uv.y, 0);
+        let start = ki + 1 - self.nurbs.order;
+        let mut point = Vec4::ZERO;
+        for (k, control) in self.controls[start..start + self.nurbs.order].iter().enumerate() {
+            point += control.get_homogeneous_derivatives(uv.x, 0)[0] * basis[0][k];
+        }
+        point.truncate() / point.w
+    }
+
+    // Partial derivatives where derivatives[k][l] is taken k times along u and l times along v, up to k + l <= count.
+    // Homogeneous derivatives are projected with The NURBS Book A4.4.
+    pub fn get_derivatives(&self, uv: Vec2, count: usize) -> Vec<Vec<Vec3>> {
+        let mut homogeneous: Vec<Vec<Vec4>> = (0..=count).map(|k| vec![Vec4::ZERO; count + 1 - k]).collect();
+        let ki = self.nurbs.get_knot_index(uv.y);
+        let basis = self.nurbs.get_weighted_basis(ki, uv.y, count);
+        let start = ki + 1 - self.nurbs.order;
+        for (k, control) in self.controls[start..start + self.nurbs.order].iter().enumerate() {
+            let curve_derivatives = control.get_homogeneous_derivatives(uv.x, count);
+            for (du, row) in homogeneous.iter_mut().enumerate() {
+                for (dv, derivative) in row.iter_mut().enumerate() {
+                    *derivative += curve_derivatives[du] * basis[dv][k];
+                }
             }
-        // }else{
-        //     point = self.controls.last().expect(TWO_CONTROLS).get_point(uv.x);
-        // }
-        point
+        }
+        let weight = homogeneous[0][0].w;
+        let mut derivatives: Vec<Vec<Vec3>> = (0..=count).map(|k| vec![Vec3::ZERO; count + 1 - k]).collect();
+        for k in 0..=count {
+            for l in 0..=count - k {
+                let mut v = homogeneous[k][l].truncate();
+                for j in 1..=l {
+                    v -= derivatives[k][l - j] * get_binomial(l, j) * homogeneous[0][j].w;
+                }
+                for i in 1..=k {
+                    v -= derivatives[k - i][l] * get_binomial(k, i) * homogeneous[i][0].w;
+                    for j in 1..=l {
+                        v -= derivatives[k - i][l - j] * get_binomial(k, i) * get_binomial(l, j) * homogeneous[i][j].w;
+                    }
+                }
+                derivatives[k][l] = v / weight;
+            }
+        }
+        derivatives
+    }
+
+    pub fn get_derivative_u(&self, uv: Vec2) -> Vec3 {
+        self.get_derivatives(uv, 1)[1][0]
+    }
+
+    pub fn get_derivative_v(&self, uv: Vec2) -> Vec3 {
+        self.get_derivatives(uv, 1)[0][1]
+    }
+
+    // Unit normal flipped by nurbs.sign. Degenerate points like poles use a nearby uv toward the center.
+    pub fn get_normal(&self, uv: Vec2) -> Vec3 {
+        let d = self.get_derivatives(uv, 1);
+        let mut normal = d[1][0].cross(d[0][1]);
+        // Rounding leaves a tiny derivative at poles, so the cross product is compared with the larger one
+        let scale = d[1][0].length().max(d[0][1].length());
+        if normal.length() <= EPSILON.sqrt() * scale * scale {
+            let uv = uv + (Vec2::splat(0.5) - uv).normalize_or_zero() * 0.0001;
+            let d = self.get_derivatives(uv, 1);
+            normal = d[1][0].cross(d[0][1]);
+        }
+        normal.normalize_or_zero() * self.nurbs.sign
+    }
+
+    // Principal curvatures from the first and second fundamental forms.
+    // Curvature is positive where the surface bends toward the normal.
+    pub fn get_curvature(&self, uv: Vec2) -> FacetCurvature {
+        let d = self.get_derivatives(uv, 2);
+        let (du, dv) = (d[1][0], d[0][1]);
+        let normal = self.get_normal(uv);
+        let (e, f, g) = (du.dot(du), du.dot(dv), dv.dot(dv));
+        let (l, m, n) = (d[2][0].dot(normal), d[1][1].dot(normal), d[0][2].dot(normal));
+        let area = e * g - f * f;
+        if area <= Float::EPSILON {
+            return FacetCurvature {normal, ..Default::default()};
+        }
+        let gaussian = (l * n - m * m) / area;
+        let mean = (e * n - 2. * f * m + g * l) / (2. * area);
+        let root = (mean * mean - gaussian).max(0.).sqrt();
+        let (min, max) = (mean - root, mean + root);
+        let get_direction = |k: Float| {
+            let row0 = vec2(-(m - k * f), l - k * e);
+            let row1 = vec2(n - k * g, -(m - k * f));
+            let uv_dir = if row0.length_squared() > row1.length_squared() {row0} else {row1};
+            (du * uv_dir.x + dv * uv_dir.y).normalize_or_zero()
+        };
+        let mut max_direction = get_direction(max);
+        if root <= Float::EPSILON * mean.abs().max(1.) || max_direction == Vec3::ZERO {
+            max_direction = du.normalize_or_zero();
+        }
+        FacetCurvature {
+            normal,
+            min,
+            max,
+            min_direction: normal.cross(max_direction),
+            max_direction,
+            gaussian,
+            mean,
+        }
     }
 
     pub fn get_valid(&self) -> FacetShape {
@@ -463,4 +577,137 @@ impl FacetShape {
 //             })
 //             .collect()
 //     }
-// }
\ No newline at end of file
+// }
+#[cfg(test)]
+mod tests {
+    use crate::scalar::*;
+    use crate::{ArcTo, Circle, CurveShape, Extrude, FacetShape, Model, Path, PathValue, Revolve, Shape};
+
+    // Rational quarter of a unit sphere around the Y axis, from the equator to the pole at (0, 1, 0)
+    fn get_sphere() -> FacetShape {
+        let quarter = Path {
+            parts: vec![
+                Model::MoveTo(PathValue::Vector(vec![1., 0.])),
+                Model::ArcTo(ArcTo {
+                    to:    PathValue::Vector(vec![0., 1.]),
+                    radii: PathValue::Vector(vec![1., 1.]),
+                    sweep: true,
+                    ..Default::default()
+                }),
+            ],
+            ..Default::default()
+        };
+        let revolve = Revolve {
+            parts: vec![Model::Path(quarter)],
+            axis:  Vec3::Y,
+            angle: consts::FRAC_PI_2,
+            ..Default::default()
+        };
+        revolve.get_shapes().into_iter().find_map(|shape| match shape {
+            Shape::Facet(facet) => Some(facet),
+            _ => None,
+        }).unwrap()
+    }
+
+    fn get_uvs() -> Vec<Vec2> {
+        (1..5).flat_map(|i| (1..5).map(move |j| vec2(i as Float, j as Float) / 5.)).collect()
+    }
+
+    #[test]
+    fn plane_derivatives_are_its_sides() {
+        let line = |y: Float| CurveShape {
+            controls: vec![vec3(0., y, 0.), vec3(4., y, 0.)],
+            ..Default::default()
+        };
+        let mut plane = FacetShape {
+            controls: vec![line(0.), line(3.)],
+            ..Default::default()
+        }.get_valid();
+        for uv in get_uvs() {
+            assert!(plane.get_derivative_u(uv).distance(vec3(4., 0., 0.)) < 1e-5);
+            assert!(plane.get_derivative_v(uv).distance(vec3(0., 3., 0.)) < 1e-5);
+            assert!(plane.get_normal(uv).distance(Vec3::Z) < 1e-5);
+        }
+        plane.negate();
+        assert!(plane.get_normal(vec2(0.5, 0.5)).distance(-Vec3::Z) < 1e-5);
+    }
+
+    #[test]
+    fn sphere_derivatives_match_differences() {
+        let sphere = get_sphere();
+        let step = 1e-3;
+        for uv in get_uvs() {
+            let du = (sphere.get_point(uv + vec2(step, 0.)) - sphere.get_point(uv - vec2(step, 0.))) / (2. * step);
+            let dv = (sphere.get_point(uv + vec2(0., step)) - sphere.get_point(uv - vec2(0., step))) / (2. * step);
+            assert!(sphere.get_derivative_u(uv).distance(du) < 1e-3 * du.length().max(1.));
+            assert!(sphere.get_derivative_v(uv).distance(dv) < 1e-3 * dv.length().max(1.));
+            // Normals are radial and keep one side
+            let normal = sphere.get_normal(uv);
+            let radial = sphere.get_point(uv).normalize();
+            assert!(normal.cross(radial).length() < 1e-4);
+            assert!(normal.dot(radial) * sphere.get_normal(vec2(0.5, 0.5)).dot(sphere.get_point(vec2(0.5, 0.5))) > 0.);
+        }
+        // The pole has no cross product and falls back to a nearby uv
+        let pole = sphere.get_normal(vec2(1., 0.5));
+        assert!(pole.is_finite());
+        assert!(pole.cross(Vec3::Y).length() < 1e-3);
+    }
+
+    #[test]
+    fn sphere_curves_the_same_way_everywhere() {
+        let sphere = get_sphere();
+        for uv in get_uvs() {
+            let curvature = sphere.get_curvature(uv);
+            // Curvature is negative when the normal points away from the center
+            let sign = -curvature.normal.dot(sphere.get_point(uv)).signum();
+            assert!((curvature.min - sign).abs() < 1e-3);
+            assert!((curvature.max - sign).abs() < 1e-3);
+            assert!((curvature.mean - sign).abs() < 1e-3);
+            assert!((curvature.gaussian - 1.).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn cylinder_bends_only_around_its_axis() {
+        let extrude = Extrude {
+            parts:  vec![Model::Circle(Circle {radius: 2., ..Default::default()})],
+            length: 3.,
+            ..Default::default()
+        };
+        let facets: Vec<FacetShape> = extrude.get_shapes().into_iter().filter_map(|shape| match shape {
+            Shape::Facet(facet) => Some(facet),
+            _ => None,
+        }).collect();
+        assert!(!facets.is_empty());
+        for facet in &facets {
+            for uv in get_uvs() {
+                let curvature = facet.get_curvature(uv);
+                assert!(curvature.gaussian.abs() < 1e-4);
+                assert!((curvature.mean.abs() - 0.25).abs() < 1e-4);
+                let (flat, bent) = if curvature.min.abs() < curvature.max.abs() {
+                    (curvature.min_direction, curvature.max)
+                } else {
+                    (curvature.max_direction, curvature.min)
+                };
+                assert!((bent.abs() - 0.5).abs() < 1e-4);
+                assert!(flat.cross(Vec3::Z).length() < 1e-3);
+            }
+        }
+    }
+
+    #[test]
+    fn rational_sphere_stays_on_the_sphere() {
+        // Both directions are rational, so blending finished control curve points would leave the sphere
+        let sphere = get_sphere();
+        for i in 0..=20 {
+            for j in 0..=20 {
+                let uv = vec2(i as Float, j as Float) / 20.;
+                assert!((sphere.get_point(uv).length() - 1.).abs() < 1e-5);
+                assert!(sphere.get_derivatives(uv, 0)[0][0].distance(sphere.get_point(uv)) < 1e-6);
+            }
+        }
+        // Quarter turn at the middle of the equator
+        let middle = sphere.get_point(vec2(0., 0.5)).abs();
+        assert!(middle.distance(vec3(consts::FRAC_1_SQRT_2, 0., consts::FRAC_1_SQRT_2)) < 1e-5);
+    }
+}
@@ -0,0 +1,246 @@
+use crate::scalar::*;
+use crate::CurveShape;
+use super::{FacetShape, FacetProjection};
+
+const SPAN_SAMPLES: usize = 4;
+const CANDIDATE_COUNT: usize = 8;
+const NEWTON_STEPS: usize = 24;
+const HALVING_STEPS: usize = 8;
+const BOUNDARY_SAMPLES: usize = 8;
+const SECTION_STEPS: usize = 40;
+
+impl FacetShape {
+    // Point inversion on the trimmed facet. Targets over trimmed-away regions snap to the nearest boundary.
+    pub fn closest_point(&self, target: Vec3) -> FacetProjection {
+        let polygons = self.get_boundary_polygons();
+        let mut samples = vec![];
+        for u in self.get_sample_params(&self.controls[0].get_unique_knots()) {
+            for v in self.get_sample_params(&self.nurbs.knots) {
+                let uv = vec2(u, v);
+                if is_inside(&polygons, uv) {
+                    samples.push((uv, self.get_point(uv).distance(target)));
+                }
+            }
+        }
+        samples.sort_by(|a, b| a.1.total_cmp(&b.1));
+        let mut best = FacetProjection {
+            distance: Float::INFINITY,
+            ..Default::default()
+        };
+        for (uv, distance) in samples.into_iter().take(CANDIDATE_COUNT) {
+            let mut uv = uv;
+            let uv1 = self.get_uv_from_newton(target, uv);
+            if is_inside(&polygons, uv1) && self.get_point(uv1).distance(target) < distance {
+                uv = uv1;
+            }
+            best = self.get_closer_projection(best, target, uv);
+        }
+        for boundary in &self.boundaries {
+            let uv = self.get_uv_on_boundary(boundary, target);
+            best = self.get_closer_projection(best, target, uv);
+        }
+        best
+    }
+
+    fn get_closer_projection(&self, best: FacetProjection, target: Vec3, uv: Vec2) -> FacetProjection {
+        let point = self.get_point(uv);
+        let distance = point.distance(target);
+        if distance >= best.distance {
+            return best;
+        }
+        FacetProjection {
+            uv,
+            point,
+            normal: self.get_normal(uv),
+            distance,
+        }
+    }
+
+    // Gauss-Newton iteration on |S(u, v) - P|, clamped to the unit square
+    fn get_uv_from_newton(&self, target: Vec3, uv: Vec2) -> Vec2 {
+        let mut uv = uv;
+        for _ in 0..NEWTON_STEPS {
+            let d = self.get_derivatives(uv, 1);
+            let (point, du, dv) = (d[0][0], d[1][0], d[0][1]);
+            let delta = target - point;
+            let jacobian = mat2(
+                vec2(du.dot(du), du.dot(dv)),
+                vec2(du.dot(dv), dv.dot(dv)),
+            );
+            if jacobian.determinant().abs() <= Float::EPSILON {
+                break;
+            }
+            let mut step = jacobian.inverse() * vec2(du.dot(delta), dv.dot(delta));
+            let mut next = (uv + step).clamp(Vec2::ZERO, Vec2::ONE);
+            // Halve the step until the distance drops
+            for _ in 0..HALVING_STEPS {
+                if self.get_point(next).distance(target) < delta.length() {
+                    break;
+                }
+                step /= 2.;
+                next = (uv + step).clamp(Vec2::ZERO, Vec2::ONE);
+            }
+            if next.distance(uv) < 1e-7 {
+                return next;
+            }
+            uv = next;
+        }
+        uv
+    }
+
+    // Closest uv along a boundary curve by sampling and golden section search
+    fn get_uv_on_boundary(&self, boundary: &CurveShape, target: Vec3) -> Vec2 {
+        let count = BOUNDARY_SAMPLES * boundary.nurbs.knots.len();
+        let distance = |t: Float| self.get_point(boundary.get_point(t).truncate()).distance(target);
+        let mut best = (0, Float::INFINITY);
+        for i in 0..=count {
+            let d = distance(i as Float / count as Float);
+            if d < best.1 {
+                best = (i, d);
+            }
+        }
+        let ratio = ((5. as Float).sqrt() - 1.) / 2.;
+        let mut a = best.0.saturating_sub(1) as Float / count as Float;
+        let mut b = (best.0 + 1).min(count) as Float / count as Float;
+        for _ in 0..SECTION_STEPS {
+            let t0 = b - (b - a) * ratio;
+            let t1 = a + (b - a) * ratio;
+            if distance(t0) < distance(t1) {
+                b = t1;
+            }else{
+                a = t0;
+            }
+        }
+        boundary.get_point((a + b) / 2.).truncate()
+    }
+
+    fn get_sample_params(&self, knots: &[Float]) -> Vec<Float> {
+        let mut params = vec![];
+        for span in knots.windows(2) {
+            if span[0] < span[1] {
+                for i in 0..SPAN_SAMPLES {
+                    params.push(span[0] + (span[1] - span[0]) * i as Float / SPAN_SAMPLES as Float);
+                }
+            }
+        }
+        params.push(1.);
+        params
+    }
+
+    fn get_boundary_polygons(&self) -> Vec<Vec<Vec2>> {
+        // Each polygon is closed with the nearest boundary start so loop joints are not counted twice
+        let starts: Vec<Vec2> = self.boundaries.iter().map(|boundary| boundary.get_point(0.).truncate()).collect();
+        self.boundaries.iter().map(|boundary| {
+            let count = BOUNDARY_SAMPLES * boundary.nurbs.knots.len();
+            let mut polygon: Vec<Vec2> = (0..count).map(|i| boundary.get_point(i as Float / count as Float).truncate()).collect();
+            let end = boundary.get_point(1.).truncate();
+            let next = starts.iter().min_by(|a, b| a.distance(end).total_cmp(&b.distance(end)));
+            polygon.push(*next.unwrap_or(&end));
+            polygon
+        }).collect()
+    }
+}
+
+// Even-odd rule like the mesh tessellation. Points on the unit square edges count as inside.
+// Without boundaries the whole unit square is inside, as in FacetShape::get_valid.
+fn is_inside(polygons: &[Vec<Vec2>], uv: Vec2) -> bool {
+    if polygons.is_empty() {
+        return true;
+    }
+    let uv = uv.clamp(Vec2::splat(1e-5), Vec2::splat(1. - 1e-5));
+    let mut inside = false;
+    for polygon in polygons {
+        for edge in polygon.windows(2) {
+            let (a, b) = (edge[0], edge[1]);
+            if (a.y > uv.y) != (b.y > uv.y) && uv.x < a.x + (uv.y - a.y) * (b.x - a.x) / (b.y - a.y) {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scalar::*;
+    use crate::{ArcTo, CurveShape, FacetShape, Model, Path, PathValue, Revolve, Shape};
+
+    // Square plane from (0, 0, 0) to (4, 4, 0) with u along x and v along y
+    fn get_plane(boundaries: Vec<CurveShape>) -> FacetShape {
+        let line = |y: Float| CurveShape {
+            controls: vec![vec3(0., y, 0.), vec3(4., y, 0.)],
+            ..Default::default()
+        };
+        FacetShape {
+            controls: vec![line(0.), line(4.)],
+            boundaries,
+            ..Default::default()
+        }.get_valid()
+    }
+
+    #[test]
+    fn point_above_plane_projects_straight_down() {
+        let plane = get_plane(vec![]);
+        let projection = plane.closest_point(vec3(1., 3., 2.));
+        assert!(projection.point.distance(vec3(1., 3., 0.)) < 1e-4);
+        assert!(projection.uv.distance(vec2(0.25, 0.75)) < 1e-4);
+        assert!((projection.distance - 2.).abs() < 1e-4);
+        assert!(projection.normal.cross(Vec3::Z).length() < 1e-4);
+    }
+
+    #[test]
+    fn point_outside_trim_snaps_to_boundary() {
+        // Keep the lower left quarter of the plane
+        let corners = [vec2(0., 0.), vec2(0.5, 0.), vec2(0.5, 0.5), vec2(0., 0.5)];
+        let boundaries = (0..4).map(|i| CurveShape {
+            controls: vec![corners[i].extend(0.), corners[(i + 1) % 4].extend(0.)],
+            ..Default::default()
+        }.get_valid()).collect();
+        let plane = get_plane(boundaries);
+        let projection = plane.closest_point(vec3(3., 1., 1.));
+        assert!(projection.point.distance(vec3(2., 1., 0.)) < 1e-3);
+        assert!((projection.distance - (2. as Float).sqrt()).abs() < 1e-3);
+        let projection = plane.closest_point(vec3(3., 3., 1.));
+        assert!(projection.point.distance(vec3(2., 2., 0.)) < 1e-3);
+        assert!((projection.distance - (3. as Float).sqrt()).abs() < 1e-3);
+        let projection = plane.closest_point(vec3(1., 1., 1.));
+        assert!(projection.point.distance(vec3(1., 1., 0.)) < 1e-4);
+    }
+
+    #[test]
+    fn point_above_pole_finds_pole() {
+        // Unit hemisphere around the Y axis from the quarter circle between (1, 0) and the pole at (0, 1)
+        let quarter = Path {
+            parts: vec![
+                Model::MoveTo(PathValue::Vector(vec![1., 0.])),
+                Model::ArcTo(ArcTo {
+                    to:    PathValue::Vector(vec![0., 1.]),
+                    radii: PathValue::Vector(vec![1., 1.]),
+                    sweep: true,
+                    ..Default::default()
+                }),
+            ],
+            ..Default::default()
+        };
+        let revolve = Revolve {
+            parts: vec![Model::Path(quarter)],
+            axis:  Vec3::Y,
+            ..Default::default()
+        };
+        let facets: Vec<FacetShape> = revolve.get_shapes().into_iter().filter_map(|shape| match shape {
+            Shape::Facet(facet) => Some(facet),
+            _ => None,
+        }).collect();
+        assert!(!facets.is_empty());
+        for facet in &facets {
+            let projection = facet.closest_point(vec3(0., 3., 0.));
+            assert!(projection.point.distance(vec3(0., 1., 0.)) < 1e-3);
+            assert!((projection.distance - 2.).abs() < 1e-3);
+        }
+        let target = vec3(1., 1., 1.) * 2.;
+        let projection = facets.iter().map(|facet| facet.closest_point(target))
+            .min_by(|a, b| a.distance.total_cmp(&b.distance)).unwrap();
+        assert!(projection.point.distance(target.normalize()) < 1e-3);
+        assert!((projection.distance - (2. * (3. as Float).sqrt() - 1.)).abs() < 1e-3);
+    }
+}
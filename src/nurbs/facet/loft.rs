@@ -0,0 +1,150 @@
+use crate::scalar::*;
+use crate::{CurveShape, Parameterization};
+use crate::nurbs::Nurbs;
+use crate::nurbs::solve::{get_params, get_averaged_knots, get_spread_knots, get_basis_row, get_solution, get_least_squares};
+use super::FacetShape;
+
+impl FacetShape {
+    // Skin through sections (The NURBS Book 10.3). Sections run along u and are made compatible.
+    // A control count below the section count approximates the v direction by least squares instead.
+    // Closed lofts return to the first section with a matching v tangent.
+    pub fn loft(
+        sections: &[CurveShape], order: usize, parameterization: Parameterization,
+        control_count: usize, closed: bool,
+    ) -> FacetShape {
+        let sections = CurveShape::get_compatible(sections);
+        if sections.len() < 2 {
+            return FacetShape::default();
+        }
+        let mut rows: Vec<Vec<Vec4>> = sections.iter().map(|section| {
+            section.controls.iter().zip(&section.nurbs.weights).map(|(p, w)| (*p * *w).extend(*w)).collect()
+        }).collect();
+        if closed {
+            rows.push(rows[0].clone());
+        }
+        let columns: Vec<Vec<Vec4>> = (0..rows[0].len()).map(|i| rows.iter().map(|row| row[i]).collect()).collect();
+        // Average the parameters of every control column
+        let mut params = vec![0.; rows.len()];
+        for column in &columns {
+            let points: Vec<Vec3> = column.iter().map(|p| p.truncate() / p.w).collect();
+            for (param, u) in params.iter_mut().zip(get_params(&points, parameterization)) {
+                *param += u / columns.len() as Float;
+            }
+        }
+        let (nurbs, columns) = if control_count >= 2 && control_count < rows.len() {
+            get_approximated_columns(&columns, &params, order, control_count)
+        }else{
+            get_interpolated_columns(&columns, &params, order, closed)
+        };
+        let controls = (0..nurbs.weights.len()).map(|j| {
+            let mut curve = sections[0].clone();
+            let weighted: Vec<Vec4> = columns.iter().map(|column| column[j]).collect();
+            curve.nurbs.weights = weighted.iter().map(|p| p.w).collect();
+            curve.controls = weighted.iter().map(|p| p.truncate() / p.w).collect();
+            curve
+        }).collect();
+        FacetShape {
+            nurbs,
+            controls,
+            boundaries: vec![],
+        }.get_valid()
+    }
+}
+
+fn get_interpolated_columns(columns: &[Vec<Vec4>], params: &[Float], order: usize, closed: bool) -> (Nurbs, Vec<Vec<Vec4>>) {
+    // The extra control of a closed loft is pinned by matching the first derivative at both ends
+    let closed = closed && order > 2;
+    let mut knot_params = params.to_vec();
+    if closed {
+        knot_params.insert(0, 0.);
+    }
+    let order = order.clamp(2, knot_params.len());
+    let nurbs = Nurbs {
+        order,
+        knots: get_averaged_knots(&knot_params, order),
+        weights: vec![1.; knot_params.len()],
+        ..Default::default()
+    };
+    let mut matrix: Vec<Vec<Float>> = params.iter().map(|u| get_basis_row(&nurbs, *u, 0)).collect();
+    if closed {
+        let end = get_basis_row(&nurbs, 1., 1);
+        matrix.push(get_basis_row(&nurbs, 0., 1).iter().zip(end).map(|(a, b)| a - b).collect());
+    }
+    let columns = columns.iter().map(|column| {
+        let mut values = column.clone();
+        if closed {
+            values.push(Vec4::ZERO);
+        }
+        // Singular systems keep the sections as controls with the extra closed control on the first section
+        get_solution(matrix.clone(), values).unwrap_or_else(|| {
+            let mut controls = column.clone();
+            if closed {
+                controls.insert(0, column[0]);
+            }
+            controls
+        })
+    }).collect();
+    (nurbs, columns)
+}
+
+fn get_approximated_columns(columns: &[Vec<Vec4>], params: &[Float], order: usize, count: usize) -> (Nurbs, Vec<Vec<Vec4>>) {
+    let order = order.clamp(2, count);
+    let nurbs = Nurbs {
+        order,
+        knots: get_spread_knots(params, order, count),
+        weights: vec![1.; count],
+        ..Default::default()
+    };
+    let columns = columns.iter().map(|column| get_least_squares(column, params, &nurbs)).collect();
+    (nurbs, columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scalar::*;
+    use crate::nurbs::solve::get_params;
+    use crate::{Circle, CurveShape, FacetShape, Parameterization, Shape};
+
+    fn get_sections() -> Vec<CurveShape> {
+        [(1., 0.), (1.5, 1.), (0.8, 2.5), (1.2, 3.)].iter().map(|(radius, z)| {
+            let Shape::Curve(circle) = Circle {radius: *radius, ..Default::default()}.get_shapes().remove(0) else {
+                panic!("circle without curve");
+            };
+            circle.get_reshape(Mat4::from_translation(vec3(0., 0., *z)))
+        }).collect()
+    }
+
+    #[test]
+    fn loft_passes_through_sections() {
+        let sections = get_sections();
+        let facet = FacetShape::loft(&sections, 4, Parameterization::ChordLength, 0, false);
+        // Section parameters are averaged over the control columns like the loft does
+        let mut params = vec![0.; sections.len()];
+        for j in 0..sections[0].controls.len() {
+            let column: Vec<Vec3> = sections.iter().map(|section| section.controls[j]).collect();
+            for (param, v) in params.iter_mut().zip(get_params(&column, Parameterization::ChordLength)) {
+                *param += v / sections[0].controls.len() as Float;
+            }
+        }
+        for (section, v) in sections.iter().zip(params) {
+            for i in 0..=16 {
+                let u = i as Float / 16.;
+                assert!(facet.get_point(vec2(u, v)).distance(section.get_point(u)) < 1e-3);
+            }
+        }
+    }
+
+    #[test]
+    fn closed_loft_returns_smoothly_to_first_section() {
+        let sections = get_sections();
+        let facet = FacetShape::loft(&sections, 4, Parameterization::ChordLength, 0, true);
+        for i in 0..=16 {
+            let u = i as Float / 16.;
+            assert!(facet.get_point(vec2(u, 0.)).distance(sections[0].get_point(u)) < 1e-3);
+            assert!(facet.get_point(vec2(u, 1.)).distance(sections[0].get_point(u)) < 1e-3);
+            let start = facet.get_derivative_v(vec2(u, 0.));
+            let end = facet.get_derivative_v(vec2(u, 1.));
+            assert!(start.distance(end) < 1e-2 * (1. + start.length()));
+        }
+    }
+}
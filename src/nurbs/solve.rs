@@ -0,0 +1,118 @@
+use crate::scalar::*;
+use std::iter::Sum;
+use std::ops::{AddAssign, Div, Mul, Sub};
+use super::{Nurbs, curve::Parameterization};
+
+// Points or homogeneous points solved for as control values
+pub(super) trait Value: Copy + Default + AddAssign + Sum
+    + Sub<Output = Self> + Mul<Float, Output = Self> + Div<Float, Output = Self> {}
+
+impl<T> Value for T where T: Copy + Default + AddAssign + Sum
+    + Sub<Output = T> + Mul<Float, Output = T> + Div<Float, Output = T> {}
+
+// Normalized parameters for the points
+pub(super) fn get_params(points: &[Vec3], parameterization: Parameterization) -> Vec<Float> {
+    let mut params = vec![0.];
+    for pair in points.windows(2) {
+        let step = match parameterization {
+            Parameterization::Uniform     => 1.,
+            Parameterization::ChordLength => pair[0].distance(pair[1]),
+            Parameterization::Centripetal => pair[0].distance(pair[1]).sqrt(),
+        };
+        params.push(params[params.len() - 1] + step);
+    }
+    let last = params[params.len() - 1];
+    if last <= 0. {
+        return (0..points.len()).map(|i| i as Float / (points.len() - 1).max(1) as Float).collect();
+    }
+    params.iter().map(|u| u / last).collect()
+}
+
+// Clamped knots with interior knots averaged from the parameters (The NURBS Book 9.8)
+pub(super) fn get_averaged_knots(params: &[Float], order: usize) -> Vec<Float> {
+    let degree = order - 1;
+    let mut knots = vec![0.; order];
+    for j in 1..params.len() - degree {
+        knots.push(params[j..j + degree].iter().sum::<Float>() / degree as Float);
+    }
+    knots.extend(vec![1.; order]);
+    knots
+}
+
+// Clamped knots for fewer controls than parameters so every span holds parameters (The NURBS Book 9.69)
+pub(super) fn get_spread_knots(params: &[Float], order: usize, count: usize) -> Vec<Float> {
+    let degree = order - 1;
+    let d = params.len() as Float / (count - degree) as Float;
+    let mut knots = vec![0.; order];
+    for j in 1..count - degree {
+        let i = (j as Float * d) as usize;
+        let alpha = j as Float * d - i as Float;
+        knots.push((1. - alpha) * params[i - 1] + alpha * params[i]);
+    }
+    knots.extend(vec![1.; order]);
+    knots
+}
+
+// Unweighted basis of every control at u, or its derivative
+pub(super) fn get_basis_row(nurbs: &Nurbs, u: Float, derivative: usize) -> Vec<Float> {
+    let mut row = vec![0.; nurbs.knots.len() - nurbs.order];
+    let ki = nurbs.get_knot_index(u);
+    let basis = nurbs.get_unweighted_basis(ki, u, derivative);
+    for (k, value) in basis[derivative].iter().enumerate() {
+        row[ki + 1 - nurbs.order + k] = *value;
+    }
+    row
+}
+
+// Gaussian elimination with partial pivoting for a square system
+pub(super) fn get_solution<T: Value>(mut matrix: Vec<Vec<Float>>, mut values: Vec<T>) -> Option<Vec<T>> {
+    let size = values.len();
+    for col in 0..size {
+        let pivot = (col..size).max_by(|a, b| matrix[*a][col].abs().total_cmp(&matrix[*b][col].abs()))?;
+        if matrix[pivot][col].abs() <= EPSILON {
+            return None;
+        }
+        matrix.swap(col, pivot);
+        values.swap(col, pivot);
+        for row in col + 1..size {
+            let factor = matrix[row][col] / matrix[col][col];
+            if factor == 0. {
+                continue;
+            }
+            let pivot_row = matrix[col].clone();
+            for (value, pivot_value) in matrix[row].iter_mut().zip(pivot_row).skip(col) {
+                *value -= factor * pivot_value;
+            }
+            values[row] = values[row] - values[col] * factor;
+        }
+    }
+    for col in (0..size).rev() {
+        let sum: T = (col + 1..size).map(|k| values[k] * matrix[col][k]).sum();
+        values[col] = (values[col] - sum) / matrix[col][col];
+    }
+    Some(values)
+}
+
+// Least-squares controls with the end controls fixed on the end points (The NURBS Book 9.4.1)
+pub(super) fn get_least_squares<T: Value>(points: &[T], params: &[Float], nurbs: &Nurbs) -> Vec<T> {
+    let count = nurbs.knots.len() - nurbs.order;
+    let first = points[0];
+    let last = points[points.len() - 1];
+    let size = count - 2;
+    let mut matrix = vec![vec![0.; size]; size];
+    let mut values = vec![T::default(); size];
+    for (point, u) in points.iter().zip(params).take(points.len() - 1).skip(1) {
+        let row = get_basis_row(nurbs, *u, 0);
+        let residual = *point - first * row[0] - last * row[count - 1];
+        for i in 0..size {
+            values[i] += residual * row[i + 1];
+            for j in 0..size {
+                matrix[i][j] += row[i + 1] * row[j + 1];
+            }
+        }
+    }
+    let mut controls = vec![first];
+    controls.extend(get_solution(matrix, values).unwrap_or(vec![first; size]));
+    controls.push(last);
+    controls
+}
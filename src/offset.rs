@@ -0,0 +1,171 @@
+use crate::{get_curves, CurveShape, Model, Reshape, Revolve, Shape, Trim};
+use serde::{Deserialize, Serialize};
+use crate::scalar::*;
+
+// Largest turn in radians between neighbors that are joined into one curve before trimming
+const SMOOTH_ANGLE: Float = 0.01;
+
+// Planar curves offset by a signed distance. Positive distances go to the right of each curve,
+// which is outward for counterclockwise loops. Curves are treated as one chain in order.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Offset {
+    pub parts:     Vec<Model>,
+    pub reshape:   Reshape,
+    pub distance:  Float,
+    pub tolerance: Float,
+}
+
+impl Default for Offset {
+    fn default() -> Self {
+        Self {
+            parts:     vec![],
+            reshape:   Reshape::default(),
+            distance:  0.,
+            tolerance: 0.001,
+        }
+    }
+}
+
+impl Offset {
+    pub fn get_shapes(&self) -> Vec<Shape> {
+        let pieces: Vec<CurveShape> = get_curves(&self.parts).iter().flat_map(|curve| curve.get_smooth_pieces()).collect();
+        if pieces.is_empty() || self.distance == 0. {
+            return self.reshape.get_reshapes(pieces.into_iter().map(Shape::Curve).collect());
+        }
+        let closed = pieces[0].get_point(0.).distance(pieces[pieces.len() - 1].get_point(1.)) < self.tolerance;
+        let offsets: Vec<Option<CurveShape>> = pieces.iter().map(|piece| piece.get_offset(self.distance, self.tolerance)).collect();
+        let mut curves = vec![];
+        for (i, piece) in pieces.iter().enumerate() {
+            let Some(offset) = &offsets[i] else {
+                continue;
+            };
+            curves.push(offset.clone());
+            let j = (i + 1) % pieces.len();
+            if j == 0 && !closed {
+                continue;
+            }
+            let Some(next) = &offsets[j] else {
+                continue;
+            };
+            if let Some(join) = self.get_round_join(piece, &pieces[j], offset, next) {
+                curves.push(join);
+            }
+        }
+        let mut curves = self.get_smooth_runs(curves, closed);
+        // Overlaps are cut back where the offset pieces cross. Trim keeps what lies outside the other pieces,
+        // so inward offsets are negated like Union negated parts to keep what lies inside instead.
+        if self.distance < 0. {
+            curves.iter_mut().for_each(|curve| {curve.negate();});
+        }
+        curves = Trim::new(curves, self.tolerance).build();
+        if self.distance < 0. {
+            curves.iter_mut().for_each(|curve| {curve.negate();});
+        }
+        self.reshape.get_reshapes(curves.into_iter().map(Shape::Curve).collect())
+    }
+
+    // Trim cannot cut curves that only touch with matching tangents, like round joins and their neighbors,
+    // so each tangent continuous run of the chain becomes one curve
+    fn get_smooth_runs(&self, mut curves: Vec<CurveShape>, closed: bool) -> Vec<CurveShape> {
+        let count = curves.len();
+        let mut smooth: Vec<bool> = (0..count).map(|i| {
+            (closed || i + 1 < count) && self.is_smooth_joint(&curves[i], &curves[(i + 1) % count])
+        }).collect();
+        if closed && count > 0 {
+            let Some(i) = smooth.iter().position(|smooth| !smooth) else {
+                return vec![CurveShape::get_joined(&curves)];
+            };
+            curves.rotate_left(i + 1);
+            smooth.rotate_left(i + 1);
+        }
+        let mut runs = vec![];
+        let mut run = vec![];
+        for (curve, smooth) in curves.into_iter().zip(smooth) {
+            run.push(curve);
+            if !smooth {
+                runs.push(CurveShape::get_joined(&run));
+                run.clear();
+            }
+        }
+        runs
+    }
+
+    fn is_smooth_joint(&self, curve: &CurveShape, next: &CurveShape) -> bool {
+        if curve.get_point(1.).distance(next.get_point(0.)) > self.tolerance {
+            return false;
+        }
+        let tangent0 = curve.get_derivatives(1., 1)[1];
+        let tangent1 = next.get_derivatives(0., 1)[1];
+        tangent0.angle_between(tangent1) <= SMOOTH_ANGLE
+    }
+
+    // Arc around the shared corner where the offset pieces open a gap
+    fn get_round_join(&self, piece: &CurveShape, next_piece: &CurveShape, offset: &CurveShape, next: &CurveShape) -> Option<CurveShape> {
+        let corner = piece.get_point(1.);
+        if corner.distance(next_piece.get_point(0.)) > self.tolerance {
+            return None;
+        }
+        let (start, end) = (offset.get_point(1.), next.get_point(0.));
+        if start.distance(end) <= self.tolerance {
+            return None;
+        }
+        let tangent0 = piece.get_derivatives(1., 1)[1];
+        let tangent1 = next_piece.get_derivatives(0., 1)[1];
+        let turn = tangent0.cross(tangent1).z;
+        // Right offsets open at left turns and left offsets at right turns
+        if turn * self.distance <= 0. {
+            return None;
+        }
+        let angle = (start - corner).angle_between(end - corner);
+        let revolve = Revolve {
+            parts:  vec![Model::Point(start)],
+            center: corner,
+            axis:   vec3(0., 0., turn.signum()),
+            angle,
+            ..Default::default()
+        };
+        revolve.get_shapes().into_iter().find_map(|shape| match shape {
+            Shape::Curve(curve) => Some(curve),
+            _ => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scalar::*;
+    use crate::{Model, Offset, Rectangle, Shape};
+
+    #[test]
+    fn rectangle_offset_joins_round_corners() {
+        let rectangle = Model::Rectangle(Rectangle {half_lengths: [2., 1.], ..Default::default()});
+        let shapes = Offset {parts: vec![rectangle], distance: 0.5, ..Default::default()}.get_shapes();
+        let [Shape::Curve(curve)] = shapes.as_slice() else {
+            panic!("expected one closed curve");
+        };
+        assert!(curve.get_point(0.).distance(curve.get_point(1.)) < 1e-4);
+        for i in 0..=64 {
+            assert!(!curve.get_point(i as Float / 64.).is_nan());
+        }
+        assert!((curve.get_length() - (12. + consts::PI)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn inward_rectangle_offset_trims_corners() {
+        let rectangle = Model::Rectangle(Rectangle {half_lengths: [2., 1.], ..Default::default()});
+        let shapes = Offset {parts: vec![rectangle], distance: -0.5, ..Default::default()}.get_shapes();
+        let mut length = 0.;
+        for shape in &shapes {
+            let Shape::Curve(curve) = shape else {
+                continue;
+            };
+            length += curve.get_length();
+            for i in 0..=8 {
+                let point = curve.get_point(i as Float / 8.);
+                assert!(point.x.abs() <= 1.5 + 1e-4 && point.y.abs() <= 0.5 + 1e-4);
+            }
+        }
+        assert!((length - 8.).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,345 @@
+use crate::scalar::consts::PI;
+use crate::{get_reshaped_point, CurveShape, Model, Revolve, Shape};
+use serde::{Deserialize, Serialize};
+use crate::scalar::*;
+
+// Tagged value as written in paths, like {Vector:[x, y]}
+#[derive(Clone, Serialize, Deserialize)]
+pub enum PathValue {
+    Vector(Vec<Float>),
+}
+
+impl Default for PathValue {
+    fn default() -> Self {
+        PathValue::Vector(vec![])
+    }
+}
+
+impl PathValue {
+    // Missing components are taken from the fallback
+    pub fn get_vec3_or(&self, fallback: Vec3) -> Vec3 {
+        let PathValue::Vector(vector) = self;
+        let mut result = fallback;
+        for (i, component) in vector.iter().take(3).enumerate() {
+            result[i] = *component;
+        }
+        result
+    }
+    pub fn get_vec2_or(&self, fallback: Vec2) -> Vec2 {
+        self.get_vec3_or(fallback.extend(0.)).truncate()
+    }
+}
+
+// Chain of segments where each LineTo or ArcTo starts at the end of the previous part
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Path {
+    pub parts:   Vec<Model>,
+    pub reverse: bool,
+}
+
+impl Path {
+    pub fn get_shapes(&self) -> Vec<Shape> {
+        self.get_path_shape(Vec2::ZERO).shapes
+    }
+    fn get_path_shape(&self, pos: Vec2) -> PathShape {
+        let mut path = PathShape::new(pos);
+        path.add_parts(&self.parts);
+        if self.reverse {
+            path.get_reverse()
+        }else{
+            path
+        }
+    }
+}
+
+// Parts placed with a transform. Inside a path the parts continue the chain.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Group {
+    pub parts:    Vec<Model>,
+    pub position: PathValue,
+    pub scale:    PathValue,
+    pub angle:    Float,
+}
+
+impl Group {
+    pub fn get_shapes(&self) -> Vec<Shape> {
+        self.get_path_shape(Vec2::ZERO).shapes
+    }
+    pub fn get_matrix(&self) -> Mat4 {
+        Mat4::from_translation(self.position.get_vec3_or(Vec3::ZERO))
+            * Mat4::from_rotation_z(self.angle)
+            * Mat4::from_scale(self.scale.get_vec3_or(Vec3::ONE))
+    }
+    fn get_path_shape(&self, pos: Vec2) -> PathShape {
+        let mat4 = self.get_matrix();
+        let mut local_pos = pos;
+        if mat4.determinant() != 0. {
+            local_pos = get_reshaped_point(&pos.extend(0.), mat4.inverse()).truncate();
+        }
+        let mut path = PathShape::new(local_pos);
+        path.add_parts(&self.parts);
+        path.get_reshape(mat4)
+    }
+}
+
+// Elliptical arc from the current point like the SVG arc command.
+// Radii grow when they cannot reach the end point. Sweep means turning in the positive angle direction.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArcTo {
+    pub to:        PathValue,
+    pub radii:     PathValue,
+    pub rotation:  Float,
+    pub large_arc: bool,
+    pub sweep:     bool,
+}
+
+impl ArcTo {
+    pub fn get_shapes(&self) -> Vec<Shape> {
+        vec![Shape::Point(self.to.get_vec2_or(Vec2::ZERO).extend(0.))]
+    }
+    fn get_curves(&self, from: Vec2) -> Vec<CurveShape> {
+        let to = self.to.get_vec2_or(Vec2::ZERO);
+        let mut radii = self.radii.get_vec2_or(Vec2::ZERO).abs();
+        if from == to {
+            return vec![];
+        }
+        if radii.x == 0. || radii.y == 0. {
+            return vec![get_line(from, to)];
+        }
+        // Center parameterization (SVG 1.1 implementation notes F.6.5)
+        let rotation = Vec2::from_angle(self.rotation);
+        let half = Vec2::from_angle(-self.rotation).rotate((from - to) / 2.);
+        let lambda = (half / radii).length_squared();
+        if lambda > 1. {
+            radii *= lambda.sqrt();
+        }
+        let numerator = radii.x * radii.x * radii.y * radii.y
+            - radii.x * radii.x * half.y * half.y
+            - radii.y * radii.y * half.x * half.x;
+        let denominator = radii.x * radii.x * half.y * half.y + radii.y * radii.y * half.x * half.x;
+        let mut coefficient = (numerator / denominator).max(0.).sqrt();
+        if self.large_arc == self.sweep {
+            coefficient = -coefficient;
+        }
+        let local_center = vec2(radii.x * half.y / radii.y, -radii.y * half.x / radii.x) * coefficient;
+        let center = rotation.rotate(local_center) + (from + to) / 2.;
+        let u = (half - local_center) / radii;
+        let v = (-half - local_center) / radii;
+        let mut angle = u.perp_dot(v).atan2(u.dot(v));
+        if self.sweep && angle < 0. {
+            angle += PI * 2.;
+        }else if !self.sweep && angle > 0. {
+            angle -= PI * 2.;
+        }
+        // A unit circle arc scaled to the radii stays exact
+        let revolve = Revolve {
+            parts: vec![Model::Point(Vec2::from_angle(u.y.atan2(u.x)).extend(0.))],
+            axis:  vec3(0., 0., angle.signum()),
+            angle: angle.abs(),
+            ..Default::default()
+        };
+        let mat4 = Mat4::from_translation(center.extend(0.))
+            * Mat4::from_rotation_z(self.rotation)
+            * Mat4::from_scale(radii.extend(1.));
+        let mut curves = vec![];
+        for shape in revolve.get_shapes() {
+            if let Shape::Curve(curve) = shape {
+                curves.push(curve.get_reshape(mat4));
+            }
+        }
+        curves
+    }
+}
+
+struct PathShape {
+    shapes: Vec<Shape>,
+    start:  Option<Vec2>,
+    pos:    Vec2,
+}
+
+impl PathShape {
+    fn new(pos: Vec2) -> Self {
+        Self {
+            shapes: vec![],
+            start:  None,
+            pos,
+        }
+    }
+    fn add_parts(&mut self, parts: &[Model]) {
+        for part in parts {
+            match part {
+                Model::MoveTo(to) => self.pos = to.get_vec2_or(Vec2::ZERO),
+                Model::LineTo(to) => {
+                    let to = to.get_vec2_or(Vec2::ZERO);
+                    if to != self.pos {
+                        self.add_curves(vec![get_line(self.pos, to)], to);
+                    }
+                },
+                Model::ArcTo(arc)   => self.add_curves(arc.get_curves(self.pos), arc.to.get_vec2_or(Vec2::ZERO)),
+                Model::Path(path)   => self.add_path(path.get_path_shape(self.pos)),
+                Model::Group(group) => self.add_path(group.get_path_shape(self.pos)),
+                _ => self.shapes.extend(part.get_shapes()),
+            }
+        }
+    }
+    fn add_curves(&mut self, curves: Vec<CurveShape>, to: Vec2) {
+        if curves.is_empty() {
+            return;
+        }
+        self.start.get_or_insert(self.pos);
+        self.shapes.extend(curves.into_iter().map(Shape::Curve));
+        self.pos = to;
+    }
+    fn add_path(&mut self, path: PathShape) {
+        if let Some(start) = path.start {
+            self.start.get_or_insert(start);
+        }
+        self.shapes.extend(path.shapes);
+        self.pos = path.pos;
+    }
+    fn get_reverse(self) -> Self {
+        let Some(start) = self.start else {
+            return self;
+        };
+        Self {
+            shapes: self.shapes.iter().rev().map(|shape| shape.get_reverse()).collect(),
+            start:  Some(self.pos),
+            pos:    start,
+        }
+    }
+    fn get_reshape(self, mat4: Mat4) -> Self {
+        let reshape = |point: Vec2| get_reshaped_point(&point.extend(0.), mat4).truncate();
+        Self {
+            shapes: self.shapes.iter().map(|shape| shape.get_reshape(mat4)).collect(),
+            start:  self.start.map(reshape),
+            pos:    reshape(self.pos),
+        }
+    }
+}
+
+// Same line as the sketch LineTo action
+fn get_line(from: Vec2, to: Vec2) -> CurveShape {
+    CurveShape {
+        controls: vec![from.extend(0.), to.extend(0.)],
+        ..Default::default()
+    }.get_valid()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    // T-slot aluminum bar from the readme
+    fn get_t_slot() -> serde_json::Value {
+        let size = 10.;
+        let slot_depth = 6.1;
+        let slot_width = 3.1;
+        let slot_bevel = 0.45;
+        let corner_radius = 1.5;
+        let corner_position = size - corner_radius + (PI / 4.).cos() * corner_radius;
+        let radii = json!({"Vector": [corner_radius, corner_radius]});
+        let parts = json!([
+            {"MoveTo": {"Vector": [0, size - slot_depth]}},
+            {"LineTo": {"Vector": [2.9, size - slot_depth]}},
+            {"LineTo": {"Vector": [6.5, size - 2.5]}},
+            {"LineTo": {"Vector": [6.5, size - 1.5]}},
+            {"LineTo": {"Vector": [slot_width, size - 1.5]}},
+            {"LineTo": {"Vector": [slot_width, size - slot_bevel]}},
+            {"LineTo": {"Vector": [slot_width + slot_bevel, size]}},
+            {"LineTo": {"Vector": [size - corner_radius, size]}},
+            {"ArcTo":  {"to": {"Vector": [corner_position, corner_position]}, "radii": radii}},
+        ]);
+        let mut side_parts = vec![json!({"Group": {
+            "parts": [{"Path": {"parts": parts, "reverse": true}}],
+            "scale": {"Vector": [-1, 1, 1]},
+        }})];
+        side_parts.extend(parts.as_array().unwrap().iter().cloned());
+        let side = json!({"Path": {"parts": side_parts}});
+        let profile = json!({"Path": {"parts": [
+            side,
+            {"Group": {"parts": [side], "angle": -PI / 2.}},
+            {"Group": {"parts": [side], "angle": -PI}},
+            {"Group": {"parts": [side], "angle": -PI * 3. / 2.}},
+        ]}});
+        json!({"Extrusion": {
+            "parts": [{"Area": {"parts": [profile, {"Circle": {"radius": 2.5}}]}}],
+            "length": 100,
+        }})
+    }
+
+    fn get_arc_points(arc: &ArcTo, from: Vec2) -> Vec<Vec2> {
+        arc.get_curves(from).iter()
+            .flat_map(|curve| (0..=100).map(move |i| curve.get_point(i as Float / 100.).truncate()))
+            .collect()
+    }
+
+    #[test]
+    fn t_slot_profile_is_closed() {
+        let model: Model = serde_json::from_value(get_t_slot()).unwrap();
+        let Model::Extrude(extrude) = &model else {
+            panic!("extrusion expected");
+        };
+        let Model::Area(area) = &extrude.parts[0] else {
+            panic!("area expected");
+        };
+        let curves: Vec<CurveShape> = area.parts[0].get_shapes().into_iter().filter_map(|shape| match shape {
+            Shape::Curve(curve) => Some(curve),
+            _ => None,
+        }).collect();
+        // 4 sides of 2 mirrored halves, each with 7 lines and a corner arc
+        assert_eq!(curves.len(), 4 * 2 * 8);
+        for (curve, next) in curves.iter().zip(curves.iter().cycle().skip(1)) {
+            assert!(curve.get_point(1.).distance(next.get_point(0.)) < 1e-4);
+        }
+        assert!(model.get_shapes().iter().any(|shape| matches!(shape, Shape::Facet(_))));
+    }
+
+    #[test]
+    fn arc_radii_grow_to_reach_the_end() {
+        let arc = ArcTo {
+            to:    PathValue::Vector(vec![2., 0.]),
+            radii: PathValue::Vector(vec![0.5, 0.5]),
+            sweep: true,
+            ..Default::default()
+        };
+        let points = get_arc_points(&arc, Vec2::ZERO);
+        for point in &points {
+            assert!((point.distance(vec2(1., 0.)) - 1.).abs() < 1e-4);
+        }
+        // Positive sweep from the left of the center passes below it
+        assert!(points.iter().any(|point| point.distance(vec2(1., -1.)) < 0.05));
+        assert!(points.iter().all(|point| point.y < 1e-4));
+    }
+
+    #[test]
+    fn arc_flags_pick_the_center() {
+        let height = (3. as Float).sqrt();
+        // (large_arc, sweep, center, middle of the arc)
+        let cases = [
+            (false, true,  vec2(1., height),  vec2(1., height - 2.)),
+            (true,  true,  vec2(1., -height), vec2(1., -height - 2.)),
+            (false, false, vec2(1., -height), vec2(1., 2. - height)),
+            (true,  false, vec2(1., height),  vec2(1., height + 2.)),
+        ];
+        for (large_arc, sweep, center, middle) in cases {
+            let arc = ArcTo {
+                to:    PathValue::Vector(vec![2., 0.]),
+                radii: PathValue::Vector(vec![2., 2.]),
+                large_arc,
+                sweep,
+                ..Default::default()
+            };
+            let points = get_arc_points(&arc, Vec2::ZERO);
+            for point in &points {
+                assert!((point.distance(center) - 2.).abs() < 1e-4);
+            }
+            assert!(points.iter().any(|point| point.distance(middle) < 0.05));
+            assert!(points[0].distance(Vec2::ZERO) < 1e-4);
+            assert!(points[points.len()-1].distance(vec2(2., 0.)) < 1e-4);
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use crate::scalar::*;
 use crate::Model;
 
 #[derive(Default, Serialize, Deserialize)]
@@ -6,7 +7,7 @@ use crate::Model;
 pub struct DiscreteQuery {
     pub model:     Model,
     pub count:     usize,
-    pub tolerance: f32,   
+    pub tolerance: Float,   
     //pub one_mesh:  bool,
 }
 
@@ -27,4 +28,18 @@ impl DiscreteQuery {
             //one_mesh: self.one_mesh,
         }
     }
-}
\ No newline at end of file
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PointQuery {
+    pub model: Model,
+    pub point: Vec3,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ParamQuery {
+    pub model: Model,
+    pub u:     Float,
+}
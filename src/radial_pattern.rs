@@ -1,7 +1,7 @@
-use std::f32::consts::PI;
+use crate::scalar::consts::PI;
 use crate::{get_shapes, get_reshapes, Reshape, Model, Shape};
 use serde::{Deserialize, Serialize};
-use glam::*;
+use crate::scalar::*;
 
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -10,7 +10,7 @@ pub struct RadialPattern {
     pub parts:    Vec<Model>,
     pub reshape:  Reshape,
     pub axis:   Vec3,//[f32; 3],
-    pub angle:  f32,
+    pub angle:  Float,
     pub count:  usize,
 }
 
@@ -32,7 +32,7 @@ impl RadialPattern {
         let reshape_matrix = self.reshape.get_matrix();
         let basis_shapes = get_shapes(&self.parts);
         for i in 0..self.count {
-            let angle = self.angle * i as f32 / self.count as f32;
+            let angle = self.angle * i as Float / self.count as Float;
             let mat4 = Mat4::from_axis_angle(self.axis, angle);
             shapes.extend(get_reshapes(&basis_shapes, reshape_matrix * mat4));
         }
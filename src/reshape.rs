@@ -1,6 +1,6 @@
 use crate::{Model, Shape, get_shapes};
 use serde::{Deserialize, Serialize};
-use glam::*;
+use crate::scalar::*;
 
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -13,7 +13,7 @@ pub struct Reshape {
     pub rotation: Vec3,//[f32; 3],
     pub scale:    Vec3,//[f32; 3],
     pub axis:     Vec3,//[f32; 3],
-    pub angle:    f32,
+    pub angle:    Float,
 }
 
 impl Default for Reshape {
@@ -1,7 +1,10 @@
-use std::f32::consts::{PI, FRAC_PI_2, FRAC_PI_4, FRAC_1_SQRT_2};
+use crate::scalar::consts::{PI, FRAC_PI_2, FRAC_PI_4, FRAC_1_SQRT_2};
 use crate::{get_reshaped_point, get_shapes, nurbs::Nurbs, CurveShape, FacetShape, Reshape, Model, Rectangle, Shape};
 use serde::{Deserialize, Serialize};
-use glam::*;
+use crate::scalar::*;
+
+// Turns this far past a quarter stay in the last span instead of adding a nearly empty one
+const TURN_SLACK: Float = 0.001;
 
 // macro_rules! console_log {
 //     ($($t:tt)*) => (log(&format_args!($($t)*).to_string()))
@@ -14,7 +17,7 @@ pub struct Revolve {
     pub reshape: Reshape,
     pub center: Vec3,//[f32; 3],
     pub axis:   Vec3,//[f32; 3],
-    pub angle:  f32,
+    pub angle:  Float,
 }
 
 impl Default for Revolve {
@@ -95,15 +98,15 @@ impl Revolve {
 struct RevolveBasis {
     nurbs: Nurbs,
     axis: Vec3,
-    direction: f32,
-    base_angle: f32,
+    direction: Float,
+    base_angle: Float,
     transforms: Vec<Mat4>,
     translation: Mat4,
     reverse_translation: Mat4,
 }
 
 impl RevolveBasis {
-    fn new(center: Vec3, axis: Vec3, angle: f32) -> Self {
+    fn new(center: Vec3, axis: Vec3, angle: Float) -> Self {
         Self {
             nurbs: Nurbs {
                 sign: 1.,
@@ -120,8 +123,8 @@ impl RevolveBasis {
         }
     }
 
-    fn add_intermediate_turn_if_needed(&mut self, angle0: f32, angle1: f32, input_angle: f32) {
-        if input_angle > angle0 { 
+    fn add_intermediate_turn_if_needed(&mut self, angle0: Float, angle1: Float, input_angle: Float) {
+        if input_angle > angle0 + TURN_SLACK {
             self.base_angle = angle0; 
             self.nurbs.knots.extend([angle0, angle0]);
             self.nurbs.weights.extend([FRAC_1_SQRT_2, 1.]);
@@ -130,20 +133,20 @@ impl RevolveBasis {
         } 
     }
 
-    fn add_second_to_last_turn(&mut self, input_angle: f32) {
+    fn add_second_to_last_turn(&mut self, input_angle: Float) {
          let advance = (input_angle - self.base_angle) / 2.;
          self.nurbs.knots.extend([input_angle, input_angle, input_angle]);
          self.nurbs.weights.extend([advance.cos(), 1.]);
          self.add_transform(self.base_angle + advance, advance.cos());
     }
 
-    fn add_transform(&mut self, angle: f32, weight: f32) {
+    fn add_transform(&mut self, angle: Float, weight: Float) {
         let mat4 = self.get_matrix(angle, weight);
         self.transforms.push(mat4);
     }
 
     // TODO: fix skew/warp from diagonal axis!!!
-    fn get_matrix(&mut self, angle: f32, weight: f32) -> Mat4 {
+    fn get_matrix(&mut self, angle: Float, weight: Float) -> Mat4 {
         self.translation 
         * Mat4::from_scale(Vec3::new( 
             (1./weight)*(1.-self.axis.dot(Vec3::X).abs()) + self.axis.dot(Vec3::X).abs(),
@@ -155,3 +158,34 @@ impl RevolveBasis {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use crate::scalar::*;
+    use crate::{Model, Revolve, Shape};
+
+    // Without TURN_SLACK a turn just past a quarter got a second span so short that f32 rounding
+    // collapsed it, leaving a zero end tangent that broke offsets of rectangle corners
+    #[test]
+    fn turn_just_past_a_quarter_keeps_one_span() {
+        for extra in [1e-7, 1e-5, 1e-4] {
+            let angle = consts::FRAC_PI_2 + extra;
+            let revolve = Revolve {
+                parts: vec![Model::Point(vec3(1., 0., 0.))],
+                angle,
+                ..Default::default()
+            };
+            let Some(Shape::Curve(curve)) = revolve.get_shapes().into_iter().find(|shape| matches!(shape, Shape::Curve(_))) else {
+                panic!("revolve without curve");
+            };
+            assert_eq!(curve.controls.len(), 3);
+            for i in 0..=20 {
+                assert!((curve.get_point(i as Float / 20.).length() - 1.).abs() < 1e-5);
+            }
+            assert!(curve.get_point(1.).distance(vec3(angle.cos(), angle.sin(), 0.)) < 1e-5);
+            let tangent = curve.get_derivatives(1., 1)[1];
+            assert!(tangent.length() > 1.);
+            assert!(tangent.normalize().distance(vec3(-angle.sin(), angle.cos(), 0.)) < 1e-4);
+        }
+    }
+}
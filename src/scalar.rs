@@ -0,0 +1,80 @@
+// Scalar and vector types of the geometry kernel.
+// The f64 feature switches them to double precision. GPU textures and shaders stay in f32.
+
+pub use glam::{IVec2, ivec2, EulerRot};
+
+#[cfg(not(feature = "f64"))]
+pub use glam::{Vec2, Vec3, Vec4, Mat3, Mat4, vec2, vec3, mat2};
+
+#[cfg(not(feature = "f64"))]
+pub use std::f32::consts;
+
+#[cfg(not(feature = "f64"))]
+pub type Float = f32;
+
+#[cfg(feature = "f64")]
+pub use glam::{
+    DVec2 as Vec2, DVec3 as Vec3, DVec4 as Vec4, DMat3 as Mat3, DMat4 as Mat4,
+    dvec2 as vec2, dvec3 as vec3, dmat2 as mat2,
+};
+
+#[cfg(feature = "f64")]
+pub use std::f64::consts;
+
+#[cfg(feature = "f64")]
+pub type Float = f64;
+
+pub const EPSILON: Float = Float::EPSILON;
+pub const INFINITY: Float = Float::INFINITY;
+pub const NEG_INFINITY: Float = Float::NEG_INFINITY;
+
+// Texel value for the f32 GPU path
+#[cfg(not(feature = "f64"))]
+pub fn to_f32(value: Float) -> f32 {
+    value
+}
+
+#[cfg(feature = "f64")]
+pub fn to_f32(value: Float) -> f32 {
+    value as f32
+}
+
+// Texels read back from the f32 GPU path
+#[cfg(not(feature = "f64"))]
+pub fn from_f32(texels: Vec<f32>) -> Vec<Float> {
+    texels
+}
+
+#[cfg(feature = "f64")]
+pub fn from_f32(texels: Vec<f32>) -> Vec<Float> {
+    texels.into_iter().map(|texel| texel as Float).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn texels_round_trip_through_f32() {
+        let values: Vec<Float> = vec![0., 0.5, -3.25, 1024.];
+        let texels: Vec<f32> = values.iter().map(|value| to_f32(*value)).collect();
+        assert_eq!(from_f32(texels), values);
+    }
+
+    // 500 long part where f32 cannot hold a 1e-6 step near the far end
+    #[cfg(feature = "f64")]
+    #[test]
+    fn f64_resolves_small_steps_on_long_parts() {
+        assert_eq!(std::mem::size_of::<Float>(), 8);
+        let curve = crate::CurveShape {
+            controls: vec![vec3(0., 0., 0.), vec3(250., 100., 0.), vec3(500., 0., 0.)],
+            nurbs: crate::nurbs::Nurbs {order: 3, ..Default::default()},
+            ..Default::default()
+        }.get_valid();
+        let point = curve.get_point(0.999);
+        assert!(curve.get_point(0.999 + 1e-9).distance(point) > 0.);
+        let projection = curve.closest_point(point + vec3(0., 0., 1e-6));
+        assert!((projection.u - 0.999).abs() < 1e-9);
+        assert!((projection.distance - 1e-6).abs() < 1e-9);
+    }
+}
@@ -1,11 +1,11 @@
-use crate::{get_facets, get_vector_hash, query::DiscreteQuery, Curve, Facet, Shape};
+use crate::{get_facets, get_vector_hash, query::{DiscreteQuery, ParamQuery, PointQuery}, scalar::Float, Curve, CurveProjection, Facet, Fit, Shape};
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
 
 #[derive(Default, Serialize, Deserialize)]
 pub struct Scene {
-    pub points:    Vec<[f32; 3]>,
+    pub points:    Vec<[Float; 3]>,
     pub polylines: Vec<Polyline>,
     pub meshes:    Vec<Mesh>, 
     pub curves:    Vec<Curve>,
@@ -14,14 +14,14 @@ pub struct Scene {
 
 #[derive(Default, Serialize, Deserialize)]
 pub struct Mesh {
-    pub vector: Vec<f32>, 
+    pub vector: Vec<Float>, 
     pub trivec: Vec<usize>,
     pub digest: u64,
 }
 
 #[derive(Default, Serialize, Deserialize)]
 pub struct Polyline {
-    pub vector: Vec<f32>, 
+    pub vector: Vec<Float>, 
     pub digest: u64,
 }
 
@@ -68,4 +68,76 @@ pub fn get_facet_scene(val: JsValue) -> Result<JsValue, JsValue> {
     }
     mesh.digest = get_vector_hash(&mesh.vector);
     Ok(serde_wasm_bindgen::to_value(&mesh)?)
-}
\ No newline at end of file
+}
+
+#[wasm_bindgen]
+pub fn get_curve_projections(val: JsValue) -> Result<JsValue, JsValue> {
+    let query: PointQuery = serde_wasm_bindgen::from_value(val)?;
+    Ok(serde_wasm_bindgen::to_value(&get_projections(&query))?)
+}
+
+fn get_projections(query: &PointQuery) -> Vec<CurveProjection> {
+    let mut projections = vec![];
+    for shape in query.model.get_shapes() {
+        if let Shape::Curve(s) = &shape {
+            projections.push(s.closest_point(query.point));
+        }
+    }
+    projections
+}
+
+#[wasm_bindgen]
+pub fn get_facet_projections(val: JsValue) -> Result<JsValue, JsValue> {
+    let query: PointQuery = serde_wasm_bindgen::from_value(val)?;
+    let mut projections = vec![];
+    for shape in query.model.get_shapes() {
+        if let Shape::Facet(s) = &shape {
+            projections.push(s.closest_point(query.point));
+        }
+    }
+    Ok(serde_wasm_bindgen::to_value(&projections)?)
+}
+
+#[wasm_bindgen]
+pub fn get_curve_frames(val: JsValue) -> Result<JsValue, JsValue> {
+    let query: ParamQuery = serde_wasm_bindgen::from_value(val)?;
+    let mut frames = vec![];
+    for shape in query.model.get_shapes() {
+        if let Shape::Curve(s) = &shape {
+            frames.push(s.get_frame(query.u.clamp(0., 1.)));
+        }
+    }
+    Ok(serde_wasm_bindgen::to_value(&frames)?)
+}
+
+// Deviation report of a Fit model, or null when there are too few points
+#[wasm_bindgen]
+pub fn get_fit_report(val: JsValue) -> Result<JsValue, JsValue> {
+    let fit: Fit = serde_wasm_bindgen::from_value(val)?;
+    Ok(serde_wasm_bindgen::to_value(&fit.get_report())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::query::PointQuery;
+    use crate::scalar::*;
+    use super::get_projections;
+
+    #[test]
+    fn curve_projections_follow_each_curve() {
+        // Query as sent from JavaScript with a unit circle and a line above it
+        let query: PointQuery = serde_json::from_value(serde_json::json!({
+            "model": {"Area": {"parts": [
+                {"Circle": {"radius": 1}},
+                {"Curve": {"controls": [{"Point": [-2, 3, 0]}, {"Point": [2, 3, 0]}]}},
+            ]}},
+            "point": [0, 2, 0],
+        })).unwrap();
+        let projections = get_projections(&query);
+        assert_eq!(projections.len(), 2);
+        assert!(projections[0].point.distance(vec3(0., 1., 0.)) < 1e-4);
+        assert!((projections[0].distance - 1.).abs() < 1e-4);
+        assert!(projections[1].point.distance(vec3(0., 3., 0.)) < 1e-4);
+        assert!((projections[1].u - 0.5).abs() < 1e-4);
+    }
+}
@@ -1,7 +1,7 @@
-use std::f32::consts::{FRAC_PI_2, PI};
-use crate::{get_shapes, CurveShape, Reshape, Model, Revolve, Shape};
+use crate::scalar::consts::{FRAC_PI_2, PI};
+use crate::{get_shapes, nurbs::Nurbs, CurveShape, Reshape, Model, Revolve, Shape};
 use serde::{Deserialize, Serialize};
-use glam::*;
+use crate::scalar::*;
 
 
 #[derive(Clone, Default, Serialize, Deserialize)]
@@ -14,17 +14,45 @@ pub struct Sketch {
 
 #[derive(Clone, Serialize, Deserialize)]
 pub enum Action {
-    JumpTo([f32; 2]),
-    LineTo([f32; 2]),
+    JumpTo([Float; 2]),
+    LineTo([Float; 2]),
     Turn(Turn),
+    QuadTo(QuadTo),
+    CubicTo(CubicTo),
+    SmoothCubicTo(SmoothCubicTo),
+    SplineThrough(Vec<[Float; 2]>),
+    ArcTo([Float; 2]),
     Close(bool),
 }
 
 #[derive(Clone, Default, Serialize, Deserialize)]
 #[serde(default = "Turn::default")]
 pub struct Turn {
-    pub angle:  f32,
-    pub radius: f32,
+    pub angle:  Float,
+    pub radius: Float,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default = "QuadTo::default")]
+pub struct QuadTo {
+    pub control: [Float; 2],
+    pub to:      [Float; 2],
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default = "CubicTo::default")]
+pub struct CubicTo {
+    pub control_a: [Float; 2],
+    pub control_b: [Float; 2],
+    pub to:        [Float; 2],
+}
+
+// The first control mirrors the last control of a previous cubic, else it follows the turtle direction
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default = "SmoothCubicTo::default")]
+pub struct SmoothCubicTo {
+    pub control_b: [Float; 2],
+    pub to:        [Float; 2],
 }
 
 impl Sketch {
@@ -35,6 +63,7 @@ impl Sketch {
             actions: self.actions.clone(),
             start_point: vec2(0., 0.),
             turtle: Turtle::default(),
+            control: None,
         };
         sketch_shape.build_from_actions() 
     }
@@ -47,6 +76,7 @@ pub struct SketchShape {
     pub start_point: Vec2,
     pub turtle: Turtle,
     pub reshape: Reshape,
+    control: Option<Vec2>,
 }
 
 impl SketchShape { 
@@ -56,10 +86,21 @@ impl SketchShape {
     pub fn build_from_actions(&mut self) -> Vec<Shape> {
         let mut closed = false;
         for action in self.actions.clone() {
+            // Only a smooth cubic reuses the last control of the previous cubic
+            if !matches!(action, Action::SmoothCubicTo(_)) {
+                self.control = None;
+            }
             match action {
                 Action::JumpTo(p)  => self.jump_to(Vec2::from_array(p)),
                 Action::LineTo(p)  => self.line_to(Vec2::from_array(p)),
                 Action::Turn(turn) => self.turn(turn.angle, turn.radius),
+                Action::QuadTo(q)  => self.quad_to(Vec2::from_array(q.control), Vec2::from_array(q.to)),
+                Action::CubicTo(c) => self.cubic_to(
+                    Vec2::from_array(c.control_a), Vec2::from_array(c.control_b), Vec2::from_array(c.to)
+                ),
+                Action::SmoothCubicTo(c) => self.smooth_cubic_to(Vec2::from_array(c.control_b), Vec2::from_array(c.to)),
+                Action::SplineThrough(points) => self.spline_through(points.iter().map(|p| Vec2::from_array(*p)).collect()),
+                Action::ArcTo(p)   => self.arc_to(Vec2::from_array(p)),
                 Action::Close(_)   => {
                     closed = true;
                     self.close()
@@ -88,11 +129,11 @@ impl SketchShape {
         self
         //self.actions.push(Action::LineTo([x, y]));
     }
-    fn jump_forward(&mut self, length: f32) -> &mut Self {
+    fn jump_forward(&mut self, length: Float) -> &mut Self {
         self.turtle.jump_forward(length);
         self
     }
-    fn line_forward(&mut self, length: f32) -> &mut Self {
+    fn line_forward(&mut self, length: Float) -> &mut Self {
         let start_point = self.turtle.pos;
         self.turtle.jump_forward(length);
         let end_point = self.turtle.pos;
@@ -104,7 +145,7 @@ impl SketchShape {
         self.shapes.push(Shape::Point(end_point.extend(0.)));
         self
     }
-    fn turn(&mut self, angle: f32, radius: f32) -> &mut Self {
+    fn turn(&mut self, angle: Float, radius: Float) -> &mut Self {
         let center = self.turtle.pos + self.turtle.dir.perp() * radius * angle.signum(); 
         if radius > 0. {
             let revolve = Revolve {
@@ -114,12 +155,89 @@ impl SketchShape {
                 angle: angle.abs(),
                 ..Default::default()//reshape: Reshape::default(),
             };
-            self.shapes.extend(revolve.get_shapes());
+            let mut shapes = revolve.get_shapes();
+            // The start point is skipped when the previous line or arc ended there,
+            // or at the sketch start which is added when the sketch ends or closes
+            let start = self.turtle.pos.extend(0.);
+            let emitted = match self.shapes.last() {
+                Some(Shape::Point(last)) => last.distance(start) <= radius * EPSILON.sqrt(),
+                None => self.turtle.pos == self.start_point,
+                _ => false,
+            };
+            if emitted && matches!(shapes.first(), Some(Shape::Point(first)) if *first == start) {
+                shapes.remove(0);
+            }
+            self.shapes.extend(shapes);
         }
         self.turtle.turn(center, angle);
         self
         //self.actions.push(Action::Turn(Turn{angle, radius}));
     }
+    fn quad_to(&mut self, control: Vec2, point: Vec2) -> &mut Self {
+        self.bezier_to(vec![self.turtle.pos, control, point])
+    }
+    fn cubic_to(&mut self, control_a: Vec2, control_b: Vec2, point: Vec2) -> &mut Self {
+        self.bezier_to(vec![self.turtle.pos, control_a, control_b, point]);
+        self.control = Some(control_b);
+        self
+    }
+    fn smooth_cubic_to(&mut self, control_b: Vec2, point: Vec2) -> &mut Self {
+        let pos = self.turtle.pos;
+        let control_a = match self.control {
+            Some(control) => pos * 2. - control,
+            None => pos + self.turtle.get_dir() * pos.distance(point) / 3.,
+        };
+        self.cubic_to(control_a, control_b, point)
+    }
+    // Catmull-Rom spline through the points as cubic Bezier pieces. The ends are extrapolated linearly.
+    fn spline_through(&mut self, points: Vec<Vec2>) -> &mut Self {
+        let mut points = [vec![self.turtle.pos], points].concat();
+        points.dedup();
+        for i in 1..points.len() {
+            let previous = if i > 1 {points[i - 2]} else {points[0] * 2. - points[1]};
+            let next = if i + 1 < points.len() {points[i + 1]} else {points[i] * 2. - points[i - 1]};
+            let control_a = points[i - 1] + (points[i] - previous) / 6.;
+            let control_b = points[i] - (next - points[i - 1]) / 6.;
+            self.bezier_to(vec![points[i - 1], control_a, control_b, points[i]]);
+        }
+        self
+    }
+    // Arc leaving tangent to the turtle direction and ending at the point
+    fn arc_to(&mut self, point: Vec2) -> &mut Self {
+        let normal = self.turtle.get_dir().perp();
+        let chord = point - self.turtle.pos;
+        // A point straight ahead or behind gets a line, also after rounding in earlier turns
+        if normal.dot(chord).abs() <= chord.length() * EPSILON.sqrt() {
+            return self.line_to(point);
+        }
+        let radius = chord.length_squared() / (2. * normal.dot(chord));
+        let center = self.turtle.pos + normal * radius;
+        let angle = get_sweep(center, self.turtle.pos, point, radius.signum());
+        self.turn(angle, radius.abs());
+        self.turtle.pos = point;
+        self
+    }
+    fn bezier_to(&mut self, controls: Vec<Vec2>) -> &mut Self {
+        let point = controls[controls.len() - 1];
+        if controls.iter().all(|control| *control == point) {
+            return self;
+        }
+        let order = controls.len();
+        let curve = CurveShape::from_nurbs_and_controls(Nurbs {
+            order,
+            knots: [vec![0.; order], vec![1.; order]].concat(),
+            weights: vec![1.; order],
+            ..Default::default()
+        }, controls.iter().map(|control| control.extend(0.)).collect());
+        self.shapes.push(Shape::Curve(curve));
+        self.shapes.push(Shape::Point(point.extend(0.)));
+        // The end tangent points away from the last distinct control
+        if let Some(control) = controls.iter().rev().find(|control| **control != point) {
+            self.turtle.dir = (point - *control).normalize();
+        }
+        self.turtle.pos = point;
+        self
+    }
     fn close(&mut self) -> &mut Self {
         self.line_to(self.start_point);
         self
@@ -138,10 +256,14 @@ impl Turtle {
         self.dir = (to - self.pos).normalize();
         self.pos = to;
     }
-    fn jump_forward(&mut self, length: f32) {
+    fn jump_forward(&mut self, length: Float) {
         self.pos += self.dir * length;
     }
-    fn turn(&mut self, center: Vec2, angle: f32) {
+    // Zero before the turtle has a heading
+    fn get_dir(&self) -> Vec2 {
+        if self.dir.is_finite() {self.dir} else {Vec2::ZERO}
+    }
+    fn turn(&mut self, center: Vec2, angle: Float) {
         let mat3 = Mat3::from_translation(center)
             * Mat3::from_axis_angle(Vec3::Z, angle)
             * Mat3::from_translation(-center);
@@ -154,7 +276,7 @@ impl Turtle {
 #[serde(default)]
 pub struct Circle {
     pub center:  Vec2, 
-    pub radius:  f32,
+    pub radius:  Float,
     pub reverse: bool,
     pub arrows: usize,
 }
@@ -172,7 +294,7 @@ impl Circle {
             if let Shape::Curve(circle) = shapes[0].clone() {
                 for i in 0..self.arrows {
                     let mut curve = CurveShape::default();
-                    let arrow = circle.get_arrow(i as f32 / (self.arrows - 1) as f32);
+                    let arrow = circle.get_arrow(i as Float / (self.arrows - 1) as Float);
                     curve.controls.push(arrow.point);
                     curve.controls.push(arrow.point + arrow.delta);
                     shapes.push(Shape::Curve(curve.get_valid()));
@@ -188,11 +310,11 @@ impl Circle {
 #[derive(Clone, Default, Serialize, Deserialize)]
 #[serde(default = "Rectangle::default")]
 pub struct Rectangle {
-    pub half_lengths: [f32; 2],
-    pub lengths:      [f32; 2],
-    pub point_a:   [f32; 2], 
-    pub point_b:   [f32; 2], 
-    pub radius:    f32,
+    pub half_lengths: [Float; 2],
+    pub lengths:      [Float; 2],
+    pub point_a:   [Float; 2], 
+    pub point_b:   [Float; 2], 
+    pub radius:    Float,
     pub reverse:   bool,
 }
 
@@ -235,11 +357,11 @@ impl Rectangle {
 #[derive(Clone, Default, Serialize, Deserialize)]
 #[serde(default = "Slot::default")]
 pub struct Slot {
-    pub length:      f32,
-    pub half_length: f32,
-    pub point_a:   [f32; 2], 
-    pub point_b:   [f32; 2], 
-    pub radius:    f32,
+    pub length:      Float,
+    pub half_length: Float,
+    pub point_a:   [Float; 2], 
+    pub point_b:   [Float; 2], 
+    pub radius:    Float,
     pub reverse:   bool,
 }
 
@@ -267,31 +389,296 @@ impl Slot {
     }
 }
 
+// Regular polygon with a vertex at the top before rotation. A star ratio above zero adds inner vertices
+// at that fraction of the radius between the outer ones.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Polygon {
+    pub center:        Vec2,
+    pub sides:         usize,
+    pub radius:        Float,
+    pub inscribed:     bool,
+    pub corner_radius: Float,
+    pub star_ratio:    Float,
+    pub rotation:      Float,
+    pub reverse:       bool,
+}
+
+impl Default for Polygon {
+    fn default() -> Self {
+        Self {
+            center:        Vec2::ZERO,
+            sides:         6,
+            radius:        1.,
+            inscribed:     false,
+            corner_radius: 0.,
+            star_ratio:    0.,
+            rotation:      0.,
+            reverse:       false,
+        }
+    }
+}
+
+impl Polygon {
+    pub fn get_shapes(&self) -> Vec<Shape> {
+        if self.sides < 3 || self.radius <= 0. {
+            return vec![];
+        }
+        let step = PI / self.sides as Float;
+        let radius = if self.inscribed {self.radius / step.cos()} else {self.radius};
+        let mut points = vec![];
+        for i in 0..self.sides {
+            let angle = FRAC_PI_2 + self.rotation + step * 2. * i as Float;
+            points.push(self.center + Vec2::from_angle(angle) * radius);
+            if self.star_ratio > 0. {
+                points.push(self.center + Vec2::from_angle(angle + step) * radius * self.star_ratio);
+            }
+        }
+        let count = points.len();
+        let turns: Vec<Float> = (0..count).map(|i| {
+            let previous = points[(i + count - 1) % count];
+            (points[i] - previous).angle_between(points[(i + 1) % count] - points[i])
+        }).collect();
+        // Corner arcs are limited so the setbacks of neighboring corners meet at most halfway along an edge
+        let edge = points[0].distance(points[1]);
+        let max_tangent = turns.iter().map(|turn| (turn.abs() / 2.).tan()).fold(0., Float::max);
+        let corner_radius = self.corner_radius.clamp(0., edge / 2. / max_tangent);
+        let setback = |i: usize| corner_radius * (turns[i % count].abs() / 2.).tan();
+        let mut sketch = SketchShape::default();
+        sketch.reshape.reverse = self.reverse;
+        sketch.jump_to(points[0] + (points[1] - points[0]).normalize() * setback(0));
+        sketch.turtle.dir = (points[1] - points[0]).normalize();
+        for i in 1..=count {
+            let previous = points[i - 1];
+            let point = points[i % count];
+            // Edges used up by the corner arcs are skipped so the turtle keeps its direction
+            let end = point - (point - previous).normalize() * setback(i);
+            if end.distance(sketch.turtle.pos) > edge * 1e-4 {
+                sketch.line_to(end);
+            }
+            sketch.turn(turns[i % count], corner_radius);
+        }
+        sketch.get_shapes()
+    }
+}
+
 #[derive(Clone, Default, Serialize, Deserialize)]
 #[serde(default = "Arc::default")]
 pub struct Arc {
-    pub center: [f32; 2], 
-    pub radius: f32,
-    pub angle_a: f32,
-    pub angle_b: f32,
-    pub point_a: f32,
-    pub point_b: f32,
-    pub point_c: f32,
-    //pub reverse: bool,
+    pub center:  [Float; 2], 
+    pub radius:  Float,
+    pub angle_a: Float,
+    pub angle_b: Float,
+    pub point_a: [Float; 2], 
+    pub point_b: [Float; 2], 
+    pub point_c: [Float; 2], 
+    pub tangent: [Float; 2], 
+    pub reverse: bool,
 }
 
 impl Arc {
+    // From point_a to point_c with a start tangent, else through point_b, else from angle_a to angle_b about the center
     pub fn get_shapes(&self) -> Vec<Shape> {
-        // let mut revolve = Revolve {
-        //     parts: vec![Model::Point([self.center[0] + self.radius, self.center[1], 0.])],
-        //     center: [self.center[0], self.center[1], 0.],
-        //     axis: [0., 0., 1.],
-        //     angle: self.angle,
-        //     transform: Group::default(),
-        // };
-        // //revolve.transform.reverse = self.reverse;
-        // revolve.get_shapes()
-        vec![]
+        let point_a = Vec2::from_array(self.point_a);
+        let point_b = Vec2::from_array(self.point_b);
+        let point_c = Vec2::from_array(self.point_c);
+        let tangent = Vec2::from_array(self.tangent);
+        if tangent != Vec2::ZERO {
+            // Center on the start normal at equal distance from both ends
+            let normal = tangent.normalize().perp();
+            let chord = point_c - point_a;
+            if normal.dot(chord).abs() <= EPSILON {
+                return self.get_line_shapes(point_a, point_c);
+            }
+            let center = point_a + normal * chord.length_squared() / (2. * normal.dot(chord));
+            let direction = normal.dot(center - point_a).signum();
+            self.get_arc_shapes(center, point_a, get_sweep(center, point_a, point_c, direction))
+        }else if point_a != Vec2::ZERO || point_b != Vec2::ZERO || point_c != Vec2::ZERO {
+            let Some(center) = get_circumcenter(point_a, point_b, point_c) else {
+                return self.get_line_shapes(point_a, point_c);
+            };
+            let direction = (point_b - point_a).perp_dot(point_c - point_b).signum();
+            self.get_arc_shapes(center, point_a, get_sweep(center, point_a, point_c, direction))
+        }else if self.radius > 0. && self.angle_b != self.angle_a {
+            let center = Vec2::from_array(self.center);
+            let start = center + Vec2::from_angle(self.angle_a) * self.radius;
+            self.get_arc_shapes(center, start, self.angle_b - self.angle_a)
+        }else{
+            vec![]
+        }
+    }
+
+    fn get_arc_shapes(&self, center: Vec2, start: Vec2, angle: Float) -> Vec<Shape> {
+        let mut revolve = Revolve {
+            parts:  vec![Model::Point(start.extend(0.))],
+            center: center.extend(0.),
+            axis:   vec3(0., 0., angle.signum()),
+            angle:  angle.abs(),
+            ..Default::default()
+        };
+        revolve.reshape.reverse = self.reverse;
+        revolve.get_shapes()
+    }
+
+    fn get_line_shapes(&self, start: Vec2, end: Vec2) -> Vec<Shape> {
+        let mut sketch = SketchShape::default();
+        sketch.reshape.reverse = self.reverse;
+        sketch.jump_to(start).line_to(end);
+        let mut shapes = vec![Shape::Point(start.extend(0.))];
+        shapes.extend(sketch.get_shapes());
+        shapes
+    }
+}
+
+fn get_circumcenter(a: Vec2, b: Vec2, c: Vec2) -> Option<Vec2> {
+    let (ab, ac) = (b - a, c - a);
+    let d = 2. * ab.perp_dot(ac);
+    if d.abs() <= EPSILON {
+        return None;
+    }
+    Some(a + vec2(
+        ac.y * ab.length_squared() - ab.y * ac.length_squared(),
+        ab.x * ac.length_squared() - ac.x * ab.length_squared(),
+    ) / d)
+}
+
+// Signed angle from start to end about the center going counterclockwise for a positive direction
+fn get_sweep(center: Vec2, start: Vec2, end: Vec2, direction: Float) -> Float {
+    let mut angle = (start - center).angle_between(end - center);
+    if angle * direction < 0. {
+        angle += 2. * PI * direction;
+    }
+    if angle == 0. {
+        angle = 2. * PI * direction;
+    }
+    angle
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rounded_polygon_emits_each_point_once() {
+        for polygon in [
+            Polygon {corner_radius: 0.2, ..Default::default()},
+            Polygon {corner_radius: 5., ..Default::default()},
+            Polygon {sides: 5, star_ratio: 0.5, corner_radius: 0.05, ..Default::default()},
+        ] {
+            let shapes = polygon.get_shapes();
+            let points: Vec<Vec3> = shapes.iter().filter_map(|shape| match shape {
+                Shape::Point(point) => Some(*point),
+                _ => None,
+            }).collect();
+            let curves = shapes.iter().filter(|shape| matches!(shape, Shape::Curve(_))).count();
+            assert_eq!(points.len(), curves);
+            for (i, point) in points.iter().enumerate() {
+                assert!(points[i + 1..].iter().all(|other| other.distance(*point) > 1e-4));
+            }
+        }
+    }
+
+    // Sampled arc points and the signed angle they sweep about the center
+    fn get_arc_sweep(arc: &Arc, center: Vec2) -> (Vec<Vec2>, Float) {
+        let points: Vec<Vec2> = arc.get_shapes().iter().filter_map(|shape| match shape {
+            Shape::Curve(curve) => Some(curve),
+            _ => None,
+        }).flat_map(|curve| (0..=100).map(move |i| curve.get_point(i as Float / 100.).truncate())).collect();
+        let sweep = points.windows(2).map(|pair| (pair[0] - center).angle_between(pair[1] - center)).sum();
+        (points, sweep)
+    }
+
+    fn assert_arc(arc: Arc, center: Vec2, radius: Float, start: Vec2, sweep: Float) {
+        let (points, arc_sweep) = get_arc_sweep(&arc, center);
+        for point in &points {
+            assert!((point.distance(center) - radius).abs() < 1e-4);
+        }
+        assert!(points[0].distance(start) < 1e-4);
+        assert!((arc_sweep - sweep).abs() < 1e-3);
+    }
+
+    #[test]
+    fn arc_from_center_and_angles() {
+        let arc = Arc {center: [1., 2.], radius: 2., angle_b: PI * 1.5, ..Default::default()};
+        assert_arc(arc, vec2(1., 2.), 2., vec2(3., 2.), PI * 1.5);
+        let arc = Arc {radius: 1., angle_a: PI / 2., angle_b: -PI / 2., ..Default::default()};
+        assert_arc(arc, Vec2::ZERO, 1., vec2(0., 1.), -PI);
+    }
+
+    #[test]
+    fn arc_through_three_points() {
+        let arc = Arc {point_a: [1., 0.], point_b: [0., 1.], point_c: [-1., 0.], ..Default::default()};
+        assert_arc(arc, Vec2::ZERO, 1., vec2(1., 0.), PI);
+        let arc = Arc {point_a: [1., 0.], point_b: [0., -1.], point_c: [-1., 0.], ..Default::default()};
+        assert_arc(arc, Vec2::ZERO, 1., vec2(1., 0.), -PI);
+        let arc = Arc {point_a: [1., 0.], point_b: [0., -1.], point_c: [0., 1.], ..Default::default()};
+        assert_arc(arc, Vec2::ZERO, 1., vec2(1., 0.), -PI * 1.5);
+    }
+
+    #[test]
+    fn arc_from_ends_and_start_tangent() {
+        let arc = Arc {point_c: [2., 2.], tangent: [1., 0.], ..Default::default()};
+        assert_arc(arc, vec2(0., 2.), 2., Vec2::ZERO, PI / 2.);
+        let arc = Arc {point_c: [2., 2.], tangent: [-1., 0.], ..Default::default()};
+        assert_arc(arc, vec2(0., 2.), 2., Vec2::ZERO, -PI * 1.5);
+    }
+
+    fn get_sketch_curves(actions: Vec<Action>) -> Vec<CurveShape> {
+        Sketch {actions, ..Default::default()}.get_shapes().into_iter().filter_map(|shape| match shape {
+            Shape::Curve(curve) => Some(curve),
+            _ => None,
+        }).collect()
+    }
+
+    fn get_tangent(curve: &CurveShape, u: Float) -> Vec3 {
+        curve.get_derivatives(u, 1)[1].normalize()
+    }
+
+    #[test]
+    fn smooth_cubic_continues_the_previous_cubic() {
+        let curves = get_sketch_curves(vec![
+            Action::CubicTo(CubicTo {control_a: [1., 2.], control_b: [3., 2.], to: [4., 0.]}),
+            Action::SmoothCubicTo(SmoothCubicTo {control_b: [7., 1.], to: [8., 0.]}),
+        ]);
+        assert_eq!(curves.len(), 2);
+        assert!(curves[0].get_point(1.).distance(curves[1].get_point(0.)) < 1e-5);
+        // The mirrored control keeps the whole derivative, not only its direction
+        let end = curves[0].get_derivatives(1., 1)[1];
+        let start = curves[1].get_derivatives(0., 1)[1];
+        assert!(end.distance(start) < 1e-4);
+        assert!(curves[1].controls[1].distance(vec3(5., -2., 0.)) < 1e-5);
+    }
+
+    #[test]
+    fn curves_leave_along_the_turtle_direction() {
+        let curves = get_sketch_curves(vec![
+            Action::LineTo([2., 2.]),
+            Action::SmoothCubicTo(SmoothCubicTo {control_b: [5., 3.], to: [6., 2.]}),
+            // QuadTo has its own control, placed here along the turtle direction
+            Action::QuadTo(QuadTo {control: [7., 1.], to: [9., 0.]}),
+            Action::ArcTo([10., 3.]),
+        ]);
+        assert_eq!(curves.len(), 4);
+        for pair in curves.windows(2) {
+            assert!(pair[0].get_point(1.).distance(pair[1].get_point(0.)) < 1e-4);
+            assert!(get_tangent(&pair[0], 1.).distance(get_tangent(&pair[1], 0.)) < 1e-4);
+        }
+        assert!(curves[3].get_point(1.).distance(vec3(10., 3., 0.)) < 1e-4);
+    }
+
+    #[test]
+    fn spline_passes_smoothly_through_its_points() {
+        let points = [[1., 1.], [2., 0.], [4., 2.], [5., 1.]];
+        let curves = get_sketch_curves(vec![Action::SplineThrough(points.to_vec())]);
+        assert_eq!(curves.len(), points.len());
+        for (curve, point) in curves.iter().zip(points) {
+            assert!(curve.get_point(1.).distance(Vec2::from_array(point).extend(0.)) < 1e-5);
+        }
+        for pair in curves.windows(2) {
+            let end = pair[0].get_derivatives(1., 1)[1];
+            let start = pair[1].get_derivatives(0., 1)[1];
+            assert!(end.distance(start) < 1e-4);
+        }
     }
 }
 
@@ -1,13 +1,13 @@
 use std::collections::HashMap;
-use glam::*;
+use crate::scalar::*;
 
 pub struct Spatial2 {
     pub map: HashMap<String, Vec<usize>>,
-    cell_size: f32,
+    cell_size: Float,
 }
 
 impl Spatial2 {
-    pub fn new(cell_size: f32) -> Spatial2 {
+    pub fn new(cell_size: Float) -> Spatial2 {
         let map: HashMap<String, Vec<usize>> = HashMap::new();
         Spatial2 {
             map,
@@ -40,7 +40,7 @@ impl Spatial2 {
     pub fn contains_key(&self, point: &Vec2) -> bool {
         for x in -1..2 {
             for y in -1..2 {
-                if self.map.contains_key(&self.get_spatial_key(&(*point + vec2(x as f32, y as f32)))) {
+                if self.map.contains_key(&self.get_spatial_key(&(*point + vec2(x as Float, y as Float)))) {
                     return true;
                 }
             }
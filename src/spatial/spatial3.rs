@@ -1,14 +1,14 @@
 use std::collections::HashMap;
-use glam::*;
+use crate::scalar::*;
 
 #[derive(Default)]
 pub struct Spatial3 {
     pub map: HashMap<String, Vec<usize>>,
-    cell_size: f32,
+    cell_size: Float,
 }
 
 impl Spatial3 {
-    pub fn new(cell_size: f32) -> Spatial3 {
+    pub fn new(cell_size: Float) -> Spatial3 {
         let map: HashMap<String, Vec<usize>> = HashMap::new();
         Spatial3 {
             map,
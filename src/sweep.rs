@@ -0,0 +1,271 @@
+use crate::scalar::consts::FRAC_PI_8;
+use crate::{get_curves, get_reshaped_point, get_shapes, CurveShape, FacetShape, Model, Parameterization, Reshape, Shape};
+use serde::{Deserialize, Serialize};
+use crate::scalar::*;
+
+const SPAN_STATIONS: usize = 4;
+const LOFT_ORDER: usize = 4;
+const TURN_SAMPLES: usize = 8;
+// Path turning between stations
+const TURN_STEP: Float = FRAC_PI_8 / 2.;
+
+// How the profile turns as it follows the path
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub enum SweepFrame {
+    Frenet,
+    #[default]
+    RotationMinimizing,
+    Fixed,
+}
+
+// Profile parts in the XY plane move along the path with local Z on the tangent.
+// Path curves are joined in order into one rail, so each should start where the previous one ends.
+// Twist (radians) and scale change linearly with length along the path.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Sweep {
+    pub parts:   Vec<Model>,
+    pub path:    Vec<Model>,
+    pub reshape: Reshape,
+    pub frame:   SweepFrame,
+    pub up:      Vec3,
+    pub twist:   Float,
+    pub scale:   Float,
+    pub caps:    bool,
+}
+
+impl Default for Sweep {
+    fn default() -> Self {
+        Self {
+            parts:   vec![],
+            path:    vec![],
+            reshape: Reshape::default(),
+            frame:   SweepFrame::default(),
+            up:      Vec3::Z,
+            twist:   0.,
+            scale:   1.,
+            caps:    true,
+        }
+    }
+}
+
+impl Sweep {
+    pub fn get_shapes(&self) -> Vec<Shape> {
+        let curves = get_curves(&self.path);
+        if curves.is_empty() {
+            return vec![];
+        }
+        let path = CurveShape::get_joined(&curves);
+        let stations = self.get_stations(&path);
+        let (first, last) = (stations[0], stations[stations.len() - 1]);
+        let mut shapes = vec![];
+        for shape in get_shapes(&self.parts) {
+            match &shape {
+                Shape::Point(point) => {
+                    let points: Vec<Vec3> = stations.iter().map(|mat4| get_reshaped_point(point, *mat4)).collect();
+                    shapes.extend(CurveShape::interpolate(&points, LOFT_ORDER, Parameterization::ChordLength, None, None).map(Shape::Curve));
+                    shapes.push(shape.get_reshape(first));
+                    shapes.push(shape.get_reshape(last));
+                },
+                Shape::Curve(curve) => {
+                    let sections: Vec<CurveShape> = stations.iter().map(|mat4| curve.get_reshape(*mat4)).collect();
+                    shapes.push(Shape::Facet(FacetShape::loft(&sections, LOFT_ORDER, Parameterization::ChordLength, 0, false)));
+                    shapes.push(shape.get_reshape(first));
+                    shapes.push(shape.get_reshape(last));
+                },
+                Shape::Facet(facet) => {
+                    if self.caps {
+                        shapes.push(Shape::Facet(facet.get_reverse_reshape(first)));
+                        shapes.push(Shape::Facet(facet.get_reshape(last)));
+                    }
+                },
+            }
+        }
+        self.reshape.get_reshapes(shapes)
+    }
+
+    // Profile placement at stations evenly spaced along the path.
+    // The count follows how far the path turns so densely interpolated paths stay light.
+    fn get_stations(&self, path: &CurveShape) -> Vec<Mat4> {
+        let spans = path.get_unique_knots().len().max(2) - 1;
+        let samples = TURN_SAMPLES * spans * (path.nurbs.order - 1);
+        let tangents: Vec<Vec3> = (0..=samples).map(|i| path.get_frame(i as Float / samples as Float).tangent).collect();
+        let turning: Float = tangents.windows(2).map(|pair| pair[0].angle_between(pair[1])).filter(|angle| angle.is_finite()).sum();
+        let count = ((turning / TURN_STEP) as usize).max(SPAN_STATIONS * (path.nurbs.order - 1))
+            + (self.twist.abs() / FRAC_PI_8) as usize + 1;
+        let params = path.get_evenly_spaced_params(count);
+        let frames = self.get_frames(path, &params);
+        frames.iter().enumerate().map(|(i, frame)| {
+            let t = i as Float / (count - 1) as Float;
+            *frame
+                * Mat4::from_rotation_z(self.twist * t)
+                * Mat4::from_scale(Vec3::splat(1. - t + self.scale * t))
+        }).collect()
+    }
+
+    fn get_frames(&self, path: &CurveShape, params: &[Float]) -> Vec<Mat4> {
+        let mut frames = vec![];
+        let mut normal = Vec3::ZERO;
+        let mut previous: Option<(Vec3, Vec3)> = None;
+        for u in params {
+            let frame = path.get_frame(*u);
+            let tangent = frame.tangent;
+            normal = match self.frame {
+                // Straight stretches have no Frenet normal and carry the previous one instead
+                SweepFrame::Frenet if frame.curvature > 0. => frame.normal,
+                SweepFrame::Frenet => get_reflected_normal(previous, frame.point, tangent, normal),
+                SweepFrame::Fixed => {
+                    let fixed = self.up.cross(tangent).normalize_or_zero();
+                    if fixed == Vec3::ZERO {get_reflected_normal(previous, frame.point, tangent, normal)} else {fixed}
+                },
+                SweepFrame::RotationMinimizing => get_reflected_normal(previous, frame.point, tangent, normal),
+            };
+            if normal == Vec3::ZERO {
+                normal = get_first_normal(self.up, tangent);
+            }
+            previous = Some((frame.point, tangent));
+            frames.push(Mat4::from_cols(
+                normal.extend(0.),
+                tangent.cross(normal).extend(0.),
+                tangent.extend(0.),
+                frame.point.extend(1.),
+            ));
+        }
+        frames
+    }
+}
+
+// Normal at the first station with the profile Y axis toward up where possible.
+// Paths along up keep the profile X axis on global X like Extrude.
+fn get_first_normal(up: Vec3, tangent: Vec3) -> Vec3 {
+    let normal = up.cross(tangent).normalize_or_zero();
+    if normal != Vec3::ZERO {
+        return normal;
+    }
+    let normal = (Vec3::X - tangent * tangent.x).normalize_or_zero();
+    if normal == Vec3::ZERO {
+        tangent.any_orthonormal_vector()
+    }else{
+        normal
+    }
+}
+
+// Double reflection step of the rotation minimizing frame (Wang et al. 2008)
+fn get_reflected_normal(previous: Option<(Vec3, Vec3)>, point: Vec3, tangent: Vec3, normal: Vec3) -> Vec3 {
+    let Some((point0, tangent0)) = previous else {
+        return Vec3::ZERO;
+    };
+    let v1 = point - point0;
+    let c1 = v1.dot(v1);
+    if c1 <= EPSILON {
+        return normal;
+    }
+    let normal_l = normal - v1 * (2. / c1 * v1.dot(normal));
+    let tangent_l = tangent0 - v1 * (2. / c1 * v1.dot(tangent0));
+    let v2 = tangent - tangent_l;
+    let c2 = v2.dot(v2);
+    if c2 <= EPSILON {
+        return normal_l.normalize_or_zero();
+    }
+    (normal_l - v2 * (2. / c2 * v2.dot(normal_l))).normalize_or_zero()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Area, ArcTo, Circle, Curve, Extrude, Path, PathValue};
+
+    fn get_line_path(to: Vec3) -> Vec<Model> {
+        vec![Model::Curve(Curve {
+            controls: vec![Model::Point(Vec3::ZERO), Model::Point(to)],
+            ..Default::default()
+        })]
+    }
+
+    fn get_curve_shapes(shapes: &[Shape]) -> Vec<CurveShape> {
+        shapes.iter().filter_map(|shape| match shape {
+            Shape::Curve(curve) => Some(curve.clone()),
+            _ => None,
+        }).collect()
+    }
+
+    fn get_facet_shapes(shapes: &[Shape]) -> Vec<FacetShape> {
+        shapes.iter().filter_map(|shape| match shape {
+            Shape::Facet(facet) => Some(facet.clone()),
+            _ => None,
+        }).collect()
+    }
+
+    #[test]
+    fn straight_sweep_matches_extrude() {
+        let circle = Model::Circle(Circle {radius: 1.5, ..Default::default()});
+        for frame in [SweepFrame::Frenet, SweepFrame::RotationMinimizing, SweepFrame::Fixed] {
+            let sweep = Sweep {parts: vec![circle.clone()], path: get_line_path(vec3(0., 0., 2.)), frame, ..Default::default()};
+            let extrude = Extrude {parts: vec![circle.clone()], length: 2., ..Default::default()};
+            let swept = get_facet_shapes(&sweep.get_shapes());
+            let extruded = get_facet_shapes(&extrude.get_shapes());
+            for i in 0..=8 {
+                for j in 0..=8 {
+                    let uv = vec2(i as Float / 8., j as Float / 8.);
+                    assert!(swept[0].get_point(uv).distance(extruded[0].get_point(uv)) < 1e-3);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn sweep_follows_every_path_segment() {
+        let path = Model::Path(Path {
+            parts: vec![
+                Model::LineTo(PathValue::Vector(vec![4., 0.])),
+                Model::ArcTo(ArcTo {to: PathValue::Vector(vec![6., 2.]), radii: PathValue::Vector(vec![2., 2.]), sweep: true, ..Default::default()}),
+            ],
+            ..Default::default()
+        });
+        // The profile X axis stays on the inside of the turn, so offset points run parallel to the path
+        for frame in [SweepFrame::Frenet, SweepFrame::RotationMinimizing, SweepFrame::Fixed] {
+            for offset in [0., 0.5] {
+                let sweep = Sweep {parts: vec![Model::Point(vec3(offset, 0., 0.))], path: vec![path.clone()], frame, ..Default::default()};
+                let shapes = sweep.get_shapes();
+                let curve = &get_curve_shapes(&shapes)[0];
+                let end = vec3(6. - offset, 2., 0.);
+                assert!(curve.get_point(1.).distance(end) < 1e-3);
+                assert!(curve.closest_point(vec3(2., offset, 0.)).distance < 1e-3);
+                let middle = vec3(4., 2., 0.) + vec3(1., -1., 0.).normalize() * (2. - offset);
+                assert!(curve.closest_point(middle).distance < 1e-2);
+                assert!(matches!(shapes.last(), Some(Shape::Point(point)) if point.distance(end) < 1e-3));
+            }
+        }
+    }
+
+    #[test]
+    fn twist_and_scale_change_along_the_path() {
+        let sweep = Sweep {
+            parts: vec![Model::Point(Vec3::X)],
+            path:  get_line_path(vec3(0., 0., 2.)),
+            twist: consts::FRAC_PI_2,
+            scale: 2.,
+            ..Default::default()
+        };
+        let shapes = sweep.get_shapes();
+        let curve = &get_curve_shapes(&shapes)[0];
+        assert!(curve.get_point(0.).distance(Vec3::X) < 1e-4);
+        assert!(curve.get_point(1.).distance(vec3(0., 2., 2.)) < 1e-3);
+        let middle = Vec2::from_angle(consts::FRAC_PI_4).extend(1.) * vec3(1.5, 1.5, 1.);
+        assert!(curve.closest_point(middle).distance < 1e-2);
+    }
+
+    #[test]
+    fn caps_close_swept_areas() {
+        let area = Model::Area(Area {parts: vec![Model::Circle(Circle {radius: 1., ..Default::default()})], ..Default::default()});
+        for (caps, count) in [(true, 3), (false, 1)] {
+            let sweep = Sweep {parts: vec![area.clone()], path: get_line_path(vec3(1., 0., 3.)), caps, ..Default::default()};
+            let facets = get_facet_shapes(&sweep.get_shapes());
+            assert_eq!(facets.len(), count);
+            if caps {
+                assert!(facets[1].get_point(vec2(0.5, 0.5)).distance(Vec3::ZERO) < 1e-3);
+                assert!(facets[2].get_point(vec2(0.5, 0.5)).distance(vec3(1., 0., 3.)) < 1e-3);
+            }
+        }
+    }
+}
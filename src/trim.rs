@@ -1,5 +1,5 @@
 use crate::{hit::Miss, CurveHit, CurveShape, HitTester2, Shape, Spatial3};
-use glam::*;
+use crate::scalar::*;
 
 // macro_rules! console_log {
 //     ($($t:tt)*) => (log(&format_args!($($t)*).to_string()))
@@ -15,7 +15,7 @@ pub struct Trim {
 }
 
 impl Trim { 
-    pub fn new(curves0: Vec<CurveShape>, tolerance: f32) -> Self {
+    pub fn new(curves0: Vec<CurveShape>, tolerance: Float) -> Self {
         let duplication_tolerance = tolerance * 5.; 
         Trim {
             tester: HitTester2 {
@@ -101,7 +101,7 @@ impl Trim {
         }
     }
 
-    fn test_curves(&mut self, i0: usize, i1: usize, u0: f32, u1: f32) { 
+    fn test_curves(&mut self, i0: usize, i1: usize, u0: Float, u1: Float) { 
         match self.tester.test(u0, u1) {
             Ok(hit) => {
                 self.hits[i0].push(hit.hit.0);
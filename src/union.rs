@@ -3,7 +3,6 @@ mod union3;
 
 use crate::{get_grouped_curves_and_facets, Model, Reshape, Shape};
 use serde::{Deserialize, Serialize};
-use glam::*;
 
 use self::{union2::UnionBasis2, union3::UnionBasis3};
 
@@ -1,5 +1,5 @@
 use crate::{hit::Miss, CurveHit, CurveShape, HitTester2, Shape, Spatial3};
-use glam::*;
+use crate::scalar::*;
 
 
 pub struct UnionBasis2 {
@@ -13,7 +13,7 @@ pub struct UnionBasis2 {
 }
 
 impl UnionBasis2 { 
-    pub fn new(curves0: Vec<CurveShape>, curves1: Vec<CurveShape>, tolerance: f32, same_groups: bool) -> Self {
+    pub fn new(curves0: Vec<CurveShape>, curves1: Vec<CurveShape>, tolerance: Float, same_groups: bool) -> Self {
         let duplication_tolerance = tolerance * 2.; // 5.
         UnionBasis2 {
             tester: HitTester2 {
@@ -116,7 +116,7 @@ impl UnionBasis2 {
         }
     }
 
-    fn test_curves(&mut self, i0: usize, i1: usize, u0: f32, u1: f32) { 
+    fn test_curves(&mut self, i0: usize, i1: usize, u0: Float, u1: Float) { 
         match self.tester.test(u0, u1) {
             Ok(hit) => {
                 self.hits[0][i0].push(hit.hit.0);
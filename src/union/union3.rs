@@ -1,4 +1,4 @@
-use glam::*;
+use crate::scalar::*;
 use crate::{log, HitBasis3};
 use crate::{CurveShape, FacetShape, Shape, Trim};
 use super::union2::UnionBasis2;
@@ -157,8 +157,8 @@ impl UnionBasis3 {
                 bndry.controls.clear();
                 for k in 0..facet.boundaries[j].controls.len() {
                     bndry.controls.push(facet.boundaries[j].controls[k] + vec3(
-                        100. + fi as f32 * 2.,// + (j as f32)*0.005,  
-                        gi as f32 * 2.,// + (j as f32)*0.01, 
+                        100. + fi as Float * 2.,// + (j as f32)*0.005,  
+                        gi as Float * 2.,// + (j as f32)*0.01, 
                         0.
                     ));
                 }